@@ -0,0 +1,343 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::{GF2Element, DIM, M};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AdditiveFftError {
+    DependentBasis,
+    TooManyCoefficients,
+    WrongEvaluationCount,
+}
+
+impl Display for AdditiveFftError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DependentBasis => write!(f, "basis elements are not linearly independent over GF(2)"),
+            Self::TooManyCoefficients => write!(f, "polynomial doesn't fit into the transform size"),
+            Self::WrongEvaluationCount => write!(f, "number of evaluations doesn't match the transform size"),
+        }
+    }
+}
+
+impl Error for AdditiveFftError {}
+
+// Precomputed data for one recursion step of the Gao-Mateer transform
+struct Level {
+    // last basis element, used to twist f(x) into f(beta * x)
+    twist: GF2Element<DIM>,
+    twist_inv: GF2Element<DIM>,
+    // span of the remaining (normalized) basis elements, indexed by bit mask
+    points: Vec<GF2Element<DIM>>,
+}
+
+/// Additive FFT (Gao-Mateer) over a GF(2)-subspace of the field.
+///
+/// Evaluations are ordered by bit mask: index `i` holds the value at the sum of
+/// basis elements whose positions are set in `i`.
+pub struct AdditiveFft {
+    basis: Vec<GF2Element<DIM>>,
+    levels: Vec<Level>,
+}
+
+impl AdditiveFft {
+    pub fn new(basis: Vec<GF2Element<DIM>>) -> Result<Self, AdditiveFftError> {
+        if basis.len() > M || !is_independent(&basis) {
+            return Err(AdditiveFftError::DependentBasis);
+        }
+
+        let mut levels = Vec::with_capacity(basis.len());
+        let mut current = basis.clone();
+        while let Some(twist) = current.pop() {
+            let twist_inv = twist.inverse();
+            let gammas: Vec<GF2Element<DIM>> = current.iter().map(|b| b.mul(&twist_inv)).collect();
+            let points = span(&gammas);
+
+            current = gammas.iter().map(|g| g.sqr().add(g)).collect();
+            levels.push(Level { twist, twist_inv, points });
+        }
+
+        Ok(Self { basis, levels })
+    }
+
+    /// Transform over the span of `1, x, ..., x^(k-1)`
+    pub fn standard(k: usize) -> Self {
+        let basis = (0..k).map(monomial).collect();
+        Self::new(basis).expect("monomials are independent")
+    }
+
+    pub fn size(&self) -> usize {
+        1 << self.basis.len()
+    }
+
+    pub fn basis(&self) -> &[GF2Element<DIM>] {
+        &self.basis
+    }
+
+    /// Evaluation points in the same order as the output of `forward`
+    pub fn points(&self) -> Vec<GF2Element<DIM>> {
+        span(&self.basis)
+    }
+
+    pub fn forward(&self, coefs: &[GF2Element<DIM>]) -> Result<Vec<GF2Element<DIM>>, AdditiveFftError> {
+        if coefs.len() > self.size() {
+            return Err(AdditiveFftError::TooManyCoefficients);
+        }
+
+        let mut f = coefs.to_vec();
+        f.resize(self.size(), GF2Element::ZERO);
+
+        Ok(self.forward_level(f, 0))
+    }
+
+    pub fn inverse(&self, evals: &[GF2Element<DIM>]) -> Result<Vec<GF2Element<DIM>>, AdditiveFftError> {
+        if evals.len() != self.size() {
+            return Err(AdditiveFftError::WrongEvaluationCount);
+        }
+
+        Ok(self.inverse_level(evals.to_vec(), 0))
+    }
+
+    /// Product of two polynomials, the result must have fewer than `size()` coefficients
+    pub fn mul(&self, a: &[GF2Element<DIM>], b: &[GF2Element<DIM>]) -> Result<Vec<GF2Element<DIM>>, AdditiveFftError> {
+        if a.is_empty() || b.is_empty() {
+            return Ok(Vec::new());
+        }
+        let len = a.len() + b.len() - 1;
+        if len > self.size() {
+            return Err(AdditiveFftError::TooManyCoefficients);
+        }
+
+        let ea = self.forward(a)?;
+        let eb = self.forward(b)?;
+        let ec: Vec<GF2Element<DIM>> = ea.iter().zip(eb.iter()).map(|(x, y)| x.mul(y)).collect();
+
+        let mut c = self.inverse(&ec)?;
+        c.truncate(len);
+        Ok(c)
+    }
+
+    fn forward_level(&self, mut f: Vec<GF2Element<DIM>>, l: usize) -> Vec<GF2Element<DIM>> {
+        if f.len() == 1 {
+            return f;
+        }
+        let level = &self.levels[l];
+
+        // g(x) = f(beta * x)
+        let mut t = GF2Element::ONE;
+        for c in f.iter_mut() {
+            *c = c.mul(&t);
+            t = t.mul(&level.twist);
+        }
+
+        // g(x) = g0(x^2 + x) + x * g1(x^2 + x)
+        taylor_expand(&mut f);
+        let g0 = f.iter().step_by(2).cloned().collect();
+        let g1 = f.iter().skip(1).step_by(2).cloned().collect();
+
+        let e0 = self.forward_level(g0, l + 1);
+        let e1 = self.forward_level(g1, l + 1);
+
+        let half = e0.len();
+        let mut out = vec![GF2Element::ZERO; 2 * half];
+        for i in 0..half {
+            out[i] = e0[i].add(&level.points[i].mul(&e1[i]));
+            out[i + half] = out[i].add(&e1[i]);
+        }
+
+        out
+    }
+
+    fn inverse_level(&self, e: Vec<GF2Element<DIM>>, l: usize) -> Vec<GF2Element<DIM>> {
+        if e.len() == 1 {
+            return e;
+        }
+        let level = &self.levels[l];
+
+        let half = e.len() / 2;
+        let mut e0 = Vec::with_capacity(half);
+        let mut e1 = Vec::with_capacity(half);
+        for i in 0..half {
+            let v1 = e[i].add(&e[i + half]);
+            e0.push(e[i].add(&level.points[i].mul(&v1)));
+            e1.push(v1);
+        }
+
+        let g0 = self.inverse_level(e0, l + 1);
+        let g1 = self.inverse_level(e1, l + 1);
+
+        let mut f = Vec::with_capacity(e.len());
+        for (c0, c1) in g0.into_iter().zip(g1) {
+            f.push(c0);
+            f.push(c1);
+        }
+        taylor_collapse(&mut f);
+
+        let mut t = GF2Element::ONE;
+        for c in f.iter_mut() {
+            *c = c.mul(&t);
+            t = t.mul(&level.twist_inv);
+        }
+
+        f
+    }
+}
+
+/// Multiplies polynomials (coefficients from the lowest degree) with a transform of suitable size
+pub fn mul_poly(a: &[GF2Element<DIM>], b: &[GF2Element<DIM>]) -> Vec<GF2Element<DIM>> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let len = a.len() + b.len() - 1;
+    let k = usize::BITS as usize - (len - 1).leading_zeros() as usize;
+
+    AdditiveFft::standard(k).mul(a, b).expect("transform is large enough")
+}
+
+pub fn naive_mul_poly(a: &[GF2Element<DIM>], b: &[GF2Element<DIM>]) -> Vec<GF2Element<DIM>> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let mut c = vec![GF2Element::ZERO; a.len() + b.len() - 1];
+    for i in 0..a.len() {
+        for j in 0..b.len() {
+            c[i + j] = c[i + j].add(&a[i].mul(&b[j]));
+        }
+    }
+
+    c
+}
+
+// Taylor expansion at x^2 + x in place, f.len() must be a power of two.
+// Afterwards f[2i] + f[2i + 1] * x is the i-th coefficient of the expansion.
+fn taylor_expand(f: &mut [GF2Element<DIM>]) {
+    let n = f.len();
+    if n <= 2 {
+        return;
+    }
+
+    // divide by (x^2 + x)^t = x^2t + x^t, quotient ends up in the upper half
+    let t = n / 4;
+    for i in (2 * t..n).rev() {
+        f[i - t] = f[i - t].add(&f[i]);
+    }
+
+    let (low, high) = f.split_at_mut(2 * t);
+    taylor_expand(low);
+    taylor_expand(high);
+}
+
+fn taylor_collapse(f: &mut [GF2Element<DIM>]) {
+    let n = f.len();
+    if n <= 2 {
+        return;
+    }
+
+    let t = n / 4;
+    {
+        let (low, high) = f.split_at_mut(2 * t);
+        taylor_collapse(low);
+        taylor_collapse(high);
+    }
+
+    for i in 2 * t..n {
+        f[i - t] = f[i - t].add(&f[i]);
+    }
+}
+
+fn span(basis: &[GF2Element<DIM>]) -> Vec<GF2Element<DIM>> {
+    let mut points = vec![GF2Element::ZERO];
+    for b in basis {
+        let shifted: Vec<GF2Element<DIM>> = points.iter().map(|p| p.add(b)).collect();
+        points.extend(shifted);
+    }
+
+    points
+}
+
+fn monomial(i: usize) -> GF2Element<DIM> {
+    let mut el = GF2Element::ZERO;
+    el.data[i / usize::BITS as usize] |= 1 << (i % usize::BITS as usize);
+    el
+}
+
+fn is_independent(basis: &[GF2Element<DIM>]) -> bool {
+    // echelon form, sorted by leading bit from the highest
+    let mut reduced: Vec<GF2Element<DIM>> = Vec::with_capacity(basis.len());
+    for b in basis {
+        let mut v = b.clone();
+        for r in &reduced {
+            if v.get_coef(r.deg()) {
+                v = v.add(r);
+            }
+        }
+        if v == GF2Element::ZERO {
+            return false;
+        }
+
+        let pos = reduced.iter().position(|r| r.deg() < v.deg()).unwrap_or(reduced.len());
+        reduced.insert(pos, v);
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::str::FromStr;
+    use super::*;
+
+    fn random_poly(len: usize) -> Vec<GF2Element<DIM>> {
+        (0..len).map(|_| {
+            let random: Vec<usize> = (0..2 * DIM).map(|_| rand::random::<usize>()).collect();
+            GF2Element::from(<[usize; 2 * DIM]>::try_from(random).unwrap())
+        }).collect()
+    }
+
+    fn eval(f: &[GF2Element<DIM>], x: &GF2Element<DIM>) -> GF2Element<DIM> {
+        f.iter().rev().fold(GF2Element::ZERO, |acc, c| acc.mul(x).add(c))
+    }
+
+    #[test]
+    fn forward_matches_evaluation() -> Result<(), Box<dyn Error>> {
+        let basis = vec![
+            GF2Element::from_str("050E04B10B1CA453CDA09E9F9EAF055A3F6EE35A162EC9E390CC67888B2FDE0EA1DD5B2A2C9E6E373F3DE18B5621FF810F0C338D71")?,
+            GF2Element::from_str("066A1CDA81DFBD5953500236E1D5264911779ECCBCBF1241AC2886FF71AB374B7DD0A28E6863801FF40507229FE65223587491D2CD")?,
+            GF2Element::ONE,
+        ];
+        let fft = AdditiveFft::new(basis)?;
+        let f = random_poly(fft.size());
+
+        let evals = fft.forward(&f)?;
+        for (x, y) in fft.points().iter().zip(evals.iter()) {
+            assert_eq!(&eval(&f, x), y);
+        }
+        assert_eq!(fft.inverse(&evals)?, f);
+
+        Ok(())
+    }
+
+    #[test]
+    fn mul_matches_naive() -> Result<(), Box<dyn Error>> {
+        let fft = AdditiveFft::standard(3);
+        for (la, lb) in [(4, 5), (1, 8), (3, 3)] {
+            let a = random_poly(la);
+            let b = random_poly(lb);
+            assert_eq!(fft.mul(&a, &b)?, naive_mul_poly(&a, &b));
+        }
+
+        let a = random_poly(2);
+        let b = random_poly(2);
+        assert_eq!(mul_poly(&a, &b), naive_mul_poly(&a, &b));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dependent_basis() {
+        let basis = vec![monomial(3), monomial(5), monomial(3).add(&monomial(5))];
+        assert_eq!(AdditiveFft::new(basis).err(), Some(AdditiveFftError::DependentBasis));
+        assert_eq!(AdditiveFft::new(vec![GF2Element::ZERO]).err(), Some(AdditiveFftError::DependentBasis));
+    }
+}
@@ -0,0 +1,360 @@
+use crate::matrix::MatrixError;
+use crate::{GF2Element, DIM, MOD_DEG};
+
+const WORD: usize = usize::BITS as usize;
+
+/// Dense matrix over GF(2) with rows packed into limbs the same way as `GF2Element::data`.
+///
+/// Linear maps on the field act on column vectors: column `j` of the matrix of a map is the image of `x^j`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BitMatrix {
+    rows: usize,
+    cols: usize,
+    stride: usize,
+    data: Vec<usize>,
+}
+
+impl BitMatrix {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let stride = cols.div_ceil(WORD);
+        Self {
+            rows,
+            cols,
+            stride,
+            data: vec![0; rows * stride],
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut m = Self::new(n, n);
+        for i in 0..n {
+            m.set(i, i, true);
+        }
+        m
+    }
+
+    /// Matrix of a GF(2)-linear map on the field, built by probing the polynomial basis
+    pub fn from_linear_map<F>(map: F) -> Self
+        where F: Fn(&GF2Element<DIM>) -> GF2Element<DIM>
+    {
+        let mut m = Self::new(MOD_DEG, MOD_DEG);
+        for j in 0..MOD_DEG {
+            let mut e = GF2Element::ZERO;
+            e.data[j / WORD] |= 1 << (j % WORD);
+
+            let image = map(&e);
+            for i in 0..MOD_DEG {
+                if image.get_coef(i) {
+                    m.set(i, j, true);
+                }
+            }
+        }
+        m
+    }
+
+    pub fn squaring() -> Self {
+        Self::from_linear_map(|a| a.sqr())
+    }
+
+    /// a -> a^(2^k)
+    pub fn frobenius(k: usize) -> Self {
+        Self::squaring().pow(k).expect("squaring matrix is square")
+    }
+
+    pub fn square_root() -> Self {
+        Self::squaring().inverse().expect("squaring is a bijection")
+    }
+
+    /// a -> sum of a^(2^(2i)) for i = 0..(m-1)/2, the field degree must be odd
+    pub fn half_trace() -> Self {
+        let sqr = Self::squaring();
+        let step = sqr.mul(&sqr).expect("square matrices");
+
+        let mut term = Self::identity(MOD_DEG);
+        let mut out = Self::identity(MOD_DEG);
+        for _ in 0..(MOD_DEG - 1) / 2 {
+            term = term.mul(&step).expect("square matrices");
+            out = out.add(&term).expect("same dimensions");
+        }
+
+        out
+    }
+
+    /// Trace as a map into {0, 1} in the field, using H(a)^2 + H(a) = a + Tr(a) for the half-trace H
+    pub fn trace() -> Self {
+        let half_trace = Self::half_trace();
+        Self::squaring().mul(&half_trace)
+            .and_then(|m| m.add(&half_trace))
+            .and_then(|m| m.add(&Self::identity(MOD_DEG)))
+            .expect("square matrices")
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    #[inline]
+    pub fn get(&self, r: usize, c: usize) -> bool {
+        self.data[r * self.stride + c / WORD] & (1 << (c % WORD)) != 0
+    }
+
+    #[inline]
+    pub fn set(&mut self, r: usize, c: usize, value: bool) {
+        let w = &mut self.data[r * self.stride + c / WORD];
+        if value {
+            *w |= 1 << (c % WORD);
+        } else {
+            *w &= !(1 << (c % WORD));
+        }
+    }
+
+    pub fn row(&self, r: usize) -> &[usize] {
+        &self.data[r * self.stride..(r + 1) * self.stride]
+    }
+
+    /// Row as a field element, the matrix must have at most `MOD_DEG` columns
+    pub fn row_element(&self, r: usize) -> GF2Element<DIM> {
+        assert!(self.cols <= MOD_DEG, "row doesn't fit into a field element");
+        let mut el = GF2Element::ZERO;
+        el.data[..self.stride].copy_from_slice(self.row(r));
+        el
+    }
+
+    /// Matrix-vector product with the coordinates of `v`, the matrix must be `MOD_DEG` x `MOD_DEG`
+    pub fn apply(&self, v: &GF2Element<DIM>) -> GF2Element<DIM> {
+        assert!(self.rows == MOD_DEG && self.cols == MOD_DEG, "not a map on the field");
+
+        let mut out = GF2Element::ZERO;
+        for i in 0..self.rows {
+            let parity = self.row(i).iter().zip(v.data.iter())
+                .fold(0, |acc, (a, b)| acc ^ (a & b).count_ones());
+            if parity & 1 == 1 {
+                out.data[i / WORD] |= 1 << (i % WORD);
+            }
+        }
+        out
+    }
+
+    pub fn add(&self, other: &Self) -> Result<Self, MatrixError> {
+        if self.rows != other.rows || self.cols != other.cols {
+            return Err(MatrixError::DimensionMismatch);
+        }
+
+        let mut out = self.clone();
+        for (a, b) in out.data.iter_mut().zip(other.data.iter()) {
+            *a ^= b;
+        }
+        Ok(out)
+    }
+
+    pub fn mul(&self, other: &Self) -> Result<Self, MatrixError> {
+        if self.cols != other.rows {
+            return Err(MatrixError::DimensionMismatch);
+        }
+
+        // every row of the product is a sum of rows of `other`
+        let mut out = Self::new(self.rows, other.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                if self.get(i, k) {
+                    for w in 0..out.stride {
+                        out.data[i * out.stride + w] ^= other.data[k * other.stride + w];
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn pow(&self, e: usize) -> Result<Self, MatrixError> {
+        if self.rows != self.cols {
+            return Err(MatrixError::NotSquare);
+        }
+
+        let mut result = Self::identity(self.rows);
+        let mut base = self.clone();
+        let mut e = e;
+        while e > 0 {
+            if e & 1 == 1 {
+                result = result.mul(&base)?;
+            }
+            e >>= 1;
+            if e > 0 {
+                base = base.mul(&base)?;
+            }
+        }
+        Ok(result)
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut out = Self::new(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                if self.get(i, j) {
+                    out.set(j, i, true);
+                }
+            }
+        }
+        out
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().eliminate(None).len()
+    }
+
+    pub fn inverse(&self) -> Result<Self, MatrixError> {
+        if self.rows != self.cols {
+            return Err(MatrixError::NotSquare);
+        }
+
+        let mut m = self.clone();
+        let mut inv = Self::identity(self.rows);
+        if m.eliminate(Some(&mut inv)).len() < self.rows {
+            return Err(MatrixError::Singular);
+        }
+        Ok(inv)
+    }
+
+    /// Basis of the right null space {v : self * v = 0}, one vector per row
+    pub fn kernel(&self) -> Self {
+        let mut rref = self.clone();
+        let pivots = rref.eliminate(None);
+
+        let free: Vec<usize> = (0..self.cols).filter(|c| !pivots.contains(c)).collect();
+        let mut basis = Self::new(free.len(), self.cols);
+        for (k, &f) in free.iter().enumerate() {
+            basis.set(k, f, true);
+            for (r, &p) in pivots.iter().enumerate() {
+                if rref.get(r, f) {
+                    basis.set(k, p, true);
+                }
+            }
+        }
+        basis
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for w in 0..self.stride {
+            self.data.swap(a * self.stride + w, b * self.stride + w);
+        }
+    }
+
+    fn xor_row(&mut self, dst: usize, src: usize) {
+        for w in 0..self.stride {
+            self.data[dst * self.stride + w] ^= self.data[src * self.stride + w];
+        }
+    }
+
+    // Gauss-Jordan elimination into the reduced row echelon form, row operations are
+    // repeated on `companion` if given. Returns the pivot columns.
+    fn eliminate(&mut self, mut companion: Option<&mut Self>) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        let mut row = 0;
+
+        for col in 0..self.cols {
+            if row == self.rows {
+                break;
+            }
+            let Some(p) = (row..self.rows).find(|&r| self.get(r, col)) else {
+                continue;
+            };
+            self.swap_rows(p, row);
+            if let Some(c) = companion.as_deref_mut() {
+                c.swap_rows(p, row);
+            }
+
+            for r in 0..self.rows {
+                if r != row && self.get(r, col) {
+                    self.xor_row(r, row);
+                    if let Some(c) = companion.as_deref_mut() {
+                        c.xor_row(r, row);
+                    }
+                }
+            }
+
+            pivots.push(col);
+            row += 1;
+        }
+
+        pivots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::str::FromStr;
+    use crate::solve_sq_eq::halftrace;
+    use super::*;
+
+    fn random_matrix(rows: usize, cols: usize) -> BitMatrix {
+        let mut m = BitMatrix::new(rows, cols);
+        for i in 0..rows {
+            for j in 0..cols {
+                m.set(i, j, rand::random::<bool>());
+            }
+        }
+        m
+    }
+
+    #[test]
+    fn small_matrices_test() -> Result<(), Box<dyn Error>> {
+        let a = random_matrix(20, 70);
+        let b = random_matrix(70, 30);
+        assert_eq!(a.mul(&b)?.transpose(), b.transpose().mul(&a.transpose())?);
+        assert_eq!(a.mul(&a), Err(MatrixError::DimensionMismatch));
+
+        let kernel = a.kernel();
+        assert_eq!(kernel.rows() + a.rank(), 70);
+        assert_eq!(a.mul(&kernel.transpose())?, BitMatrix::new(20, kernel.rows()));
+
+        let mut c = random_matrix(100, 100);
+        while c.rank() < 100 {
+            c = random_matrix(100, 100);
+        }
+        assert_eq!(c.mul(&c.inverse()?)?, BitMatrix::identity(100));
+
+        let mut singular = c.clone();
+        for j in 0..100 {
+            singular.set(99, j, c.get(0, j) ^ c.get(1, j));
+        }
+        assert_eq!(singular.inverse(), Err(MatrixError::Singular));
+
+        Ok(())
+    }
+
+    #[test]
+    fn squaring_test() -> Result<(), Box<dyn Error>> {
+        let a = GF2Element::from_str("009F3DB3DA40977DC47CA6385DE4F71AC696344C5F4A476619D4EF4D4903AF3EC84B7E40C3E41E14F1AF9AEBE60831234A42B286FE")?;
+        let sqr = BitMatrix::squaring();
+        assert_eq!(sqr.apply(&a), a.sqr());
+        assert_eq!(sqr.rank(), MOD_DEG);
+
+        let root = BitMatrix::square_root().apply(&a);
+        assert_eq!(root.sqr(), a);
+        assert_eq!(BitMatrix::frobenius(2).apply(&a), a.sqr().sqr());
+
+        Ok(())
+    }
+
+    #[test]
+    fn half_trace_test() -> Result<(), Box<dyn Error>> {
+        let a = GF2Element::from_str("073A5F1662A5634B30ABEF467039D38245C795D6C50B1600C2DA169BB74C819156CE000BDE8DDA14C395923ABBBE4EFA8BEC80EAE9")?;
+        assert_eq!(BitMatrix::half_trace().apply(&a), halftrace(&a));
+
+        let trace = BitMatrix::trace();
+        assert_eq!(trace.apply(&a), GF2Element::ONE);
+        assert_eq!(trace.rank(), 1);
+
+        // elements of trace zero form a hyperplane
+        let kernel = trace.kernel();
+        assert_eq!(kernel.rows(), MOD_DEG - 1);
+        assert_eq!(trace.apply(&kernel.row_element(0)), GF2Element::ZERO);
+
+        Ok(())
+    }
+}
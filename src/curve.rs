@@ -0,0 +1,231 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use vl_big_ints::UnsignedLongInt;
+use crate::field::BinaryField;
+use crate::{GF2Element, DIM};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CurveError {
+    Singular,
+    NotOnCurve,
+    NotKoblitz,
+}
+
+impl Display for CurveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Singular => write!(f, "curve with b = 0 is singular"),
+            Self::NotOnCurve => write!(f, "point doesn't lie on the curve"),
+            Self::NotKoblitz => write!(f, "curve is not a Koblitz curve"),
+        }
+    }
+}
+
+impl Error for CurveError {}
+
+/// Non-supersingular binary curve y^2 + xy = x^3 + a x^2 + b
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Curve<F = GF2Element<DIM>> {
+    a: F,
+    b: F,
+}
+
+/// Affine point, the identity is the point at infinity
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Point<F = GF2Element<DIM>> {
+    Infinity,
+    Affine { x: F, y: F },
+}
+
+impl<F> Point<F> {
+    pub fn is_infinity(&self) -> bool {
+        matches!(self, Self::Infinity)
+    }
+
+    pub fn x(&self) -> Option<&F> {
+        match self {
+            Self::Infinity => None,
+            Self::Affine { x, .. } => Some(x),
+        }
+    }
+
+    pub fn y(&self) -> Option<&F> {
+        match self {
+            Self::Infinity => None,
+            Self::Affine { y, .. } => Some(y),
+        }
+    }
+}
+
+impl<F: BinaryField> Curve<F> {
+    pub fn new(a: F, b: F) -> Result<Self, CurveError> {
+        if b.is_zero() {
+            return Err(CurveError::Singular);
+        }
+        Ok(Self { a, b })
+    }
+
+    pub fn a(&self) -> &F {
+        &self.a
+    }
+
+    pub fn b(&self) -> &F {
+        &self.b
+    }
+
+    /// Affine point after checking the curve equation
+    pub fn point(&self, x: F, y: F) -> Result<Point<F>, CurveError> {
+        let p = Point::Affine { x, y };
+        if self.is_on_curve(&p) { Ok(p) } else { Err(CurveError::NotOnCurve) }
+    }
+
+    pub fn is_on_curve(&self, p: &Point<F>) -> bool {
+        match p {
+            Point::Infinity => true,
+            Point::Affine { x, y } => {
+                let x2 = x.sqr();
+                let lhs = y.sqr().add(&x.mul(y));
+                let rhs = x2.mul(x).add(&self.a.mul(&x2)).add(&self.b);
+                lhs == rhs
+            }
+        }
+    }
+
+    /// -(x, y) = (x, x + y)
+    pub fn neg(&self, p: &Point<F>) -> Point<F> {
+        match p {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x, y } => Point::Affine { x: x.clone(), y: x.add(y) },
+        }
+    }
+
+    pub fn add(&self, p: &Point<F>, q: &Point<F>) -> Point<F> {
+        let (x1, y1, x2, y2) = match (p, q) {
+            (Point::Infinity, _) => return q.clone(),
+            (_, Point::Infinity) => return p.clone(),
+            (Point::Affine { x: x1, y: y1 }, Point::Affine { x: x2, y: y2 }) => (x1, y1, x2, y2),
+        };
+
+        if x1 == x2 {
+            // either the same point or its negation
+            return if y1 == y2 { self.double(p) } else { Point::Infinity };
+        }
+
+        // lambda = (y1 + y2) / (x1 + x2)
+        let x_sum = x1.add(x2);
+        let lambda = y1.add(y2).mul(&x_sum.inverse());
+        let x3 = lambda.sqr().add(&lambda).add(&x_sum).add(&self.a);
+        let y3 = lambda.mul(&x1.add(&x3)).add(&x3).add(y1);
+
+        Point::Affine { x: x3, y: y3 }
+    }
+
+    pub fn double(&self, p: &Point<F>) -> Point<F> {
+        let (x1, y1) = match p {
+            Point::Infinity => return Point::Infinity,
+            Point::Affine { x, y } => (x, y),
+        };
+        if x1.is_zero() {
+            // the point is its own negation
+            return Point::Infinity;
+        }
+
+        // lambda = x1 + y1 / x1
+        let lambda = x1.add(&y1.mul(&x1.inverse()));
+        let x3 = lambda.sqr().add(&lambda).add(&self.a);
+        let y3 = x1.sqr().add(&lambda.add(&F::one()).mul(&x3));
+
+        Point::Affine { x: x3, y: y3 }
+    }
+
+    /// k P with left-to-right double-and-add
+    pub fn mul(&self, p: &Point<F>, k: &UnsignedLongInt) -> Point<F> {
+        let mut out = Point::Infinity;
+        let Some(top) = k.get_highest_set_bit() else {
+            return out;
+        };
+
+        for i in (0..top + 1).rev() {
+            out = self.double(&out);
+            if k.get_bit(i) {
+                out = self.add(&out, p);
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::error::Error;
+    use std::str::FromStr;
+    use crate::MOD_DEG;
+    use crate::solve_sq_eq::solve;
+    use super::*;
+
+    pub(crate) fn test_curve() -> Result<Curve, Box<dyn Error>> {
+        let b = GF2Element::from_str("066A1CDA81DFBD5953500236E1D5264911779ECCBCBF1241AC2886FF71AB374B7DD0A28E6863801FF40507229FE65223587491D2CD")?;
+        Ok(Curve::new(GF2Element::ONE, b)?)
+    }
+
+    // Point with the smallest x >= start, x read as a polynomial
+    pub(crate) fn point_after(curve: &Curve, start: usize) -> Point {
+        for k in start.. {
+            let mut x = GF2Element::ZERO;
+            x.data[0] = k;
+            // y^2 + x y = x^3 + a x^2 + b
+            let x2 = x.sqr();
+            let rhs = x2.mul(&x).add(&curve.a.mul(&x2)).add(&curve.b);
+            if let Ok((y, _)) = solve(&x, &rhs) {
+                return Point::Affine { x, y };
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn group_law_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let p = point_after(&curve, 2);
+        let q = point_after(&curve, p.x().unwrap().data[0] + 1);
+        let r = curve.double(&q);
+
+        assert!(curve.is_on_curve(&p));
+        assert!(curve.is_on_curve(&r));
+        assert_eq!(curve.add(&p, &Point::Infinity), p);
+        assert_eq!(curve.add(&p, &curve.neg(&p)), Point::Infinity);
+        assert_eq!(curve.add(&p, &q), curve.add(&q, &p));
+        assert_eq!(curve.add(&curve.add(&p, &q), &r), curve.add(&p, &curve.add(&q, &r)));
+        assert_eq!(curve.add(&q, &q), r);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scalar_mul_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let p = point_after(&curve, 2);
+
+        let mut expected = Point::Infinity;
+        for k in 0..6 {
+            assert_eq!(curve.mul(&p, &UnsignedLongInt::from(k)), expected);
+            expected = curve.add(&expected, &p);
+        }
+        // (x, y) with x = 0 has order 2
+        let y = curve.b.frobenius(MOD_DEG - 1);
+        let t = curve.point(GF2Element::ZERO, y)?;
+        assert_eq!(curve.double(&t), Point::Infinity);
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+
+        assert_eq!(Curve::new(GF2Element::ONE, GF2Element::ZERO), Err(CurveError::Singular));
+        assert_eq!(curve.point(GF2Element::ONE, GF2Element::ONE), Err(CurveError::NotOnCurve));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use vl_big_ints::UnsignedLongInt;
+use crate::curve::Curve;
+use crate::field::{BinaryField, PolynomialBasis};
+use crate::hash_to_curve::{expand_message_xmd, HashFunction};
+use crate::long_int::{div_rem, is_probable_prime, is_zero};
+use crate::small_field::{SmallField, SmallFieldParams};
+use crate::validation::{check_order_with_mov_bound, MOV_BOUND};
+use crate::{GF2Element, DIM};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GenerationError {
+    CountingUnsupported,
+    NotFound,
+}
+
+impl Display for GenerationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CountingUnsupported => write!(f, "point counting backend can't count any of the candidates"),
+            Self::NotFound => write!(f, "no suitable curve within the allowed number of attempts"),
+        }
+    }
+}
+
+impl Error for GenerationError {}
+
+/// Domain separation tag of `derive_coefficients`
+pub const GENERATION_DST: &[u8] = b"GF2-CURVE-GENERATION-V01";
+
+/// Backend that returns #E of y^2 + xy = x^3 + a x^2 + b, or None for curves it can't count
+pub trait PointCounter<F: BinaryField> {
+    fn count_points(&self, a: &F, b: &F) -> Option<UnsignedLongInt>;
+}
+
+/// Enumeration of the x-coordinates, for the small table fields only:
+/// x = 0 gives one point and x != 0 gives two when Tr(x + a + b / x^2) = 0
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BruteForceCounter;
+
+impl<P: SmallFieldParams> PointCounter<SmallField<P>> for BruteForceCounter {
+    fn count_points(&self, a: &SmallField<P>, b: &SmallField<P>) -> Option<UnsignedLongInt> {
+        let affine = (1..1u32 << P::BITS)
+            .map(|v| SmallField::<P>::new(v as u16))
+            .filter(|x| x.add(a).add(&b.mul(&x.sqr().inverse())).trace().is_zero())
+            .count() as u64;
+        // the point at infinity and (0, sqrt(b))
+        Some(UnsignedLongInt::from(2 * affine + 2))
+    }
+}
+
+/// Counts only the Koblitz curves of the configured field, `AgmCounter` counts the others
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KoblitzCounter;
+
+impl PointCounter<GF2Element<DIM>> for KoblitzCounter {
+    fn count_points(&self, a: &GF2Element<DIM>, b: &GF2Element<DIM>) -> Option<UnsignedLongInt> {
+        Curve::new(a.clone(), b.clone()).ok()?.koblitz_group_order().ok()
+    }
+}
+
+/// Conditions a generated curve has to meet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurveRequirements {
+    /// #E = h n with a prime n and h <= max_cofactor
+    pub max_cofactor: u64,
+    /// q^k != 1 mod n for k <= mov_bound
+    pub mov_bound: usize,
+    pub max_attempts: usize,
+    /// Miller-Rabin rounds with the first primes as bases
+    pub primality_rounds: usize,
+}
+
+impl Default for CurveRequirements {
+    fn default() -> Self {
+        Self { max_cofactor: 4, mov_bound: MOV_BOUND, max_attempts: 10_000, primality_rounds: 40 }
+    }
+}
+
+/// Accepted coefficients with #E = cofactor * order, `attempts` counts the drawn candidates
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedCurve<F> {
+    pub a: F,
+    pub b: F,
+    pub order: UnsignedLongInt,
+    pub cofactor: UnsignedLongInt,
+    pub attempts: usize,
+}
+
+impl GeneratedCurve<GF2Element<DIM>> {
+    pub fn curve(&self) -> Curve {
+        Curve::new(self.a.clone(), self.b.clone()).expect("generated curves have b != 0")
+    }
+}
+
+/// Coefficients of candidate `attempt`: expand_message_xmd of seed || I2OSP(attempt, 8) gives
+/// 2 ceil(m / 8) bytes, each half read as a big-endian number cut to its low m bits
+pub fn derive_coefficients<F: PolynomialBasis, H: HashFunction>(seed: &[u8], attempt: usize) -> (F, F) {
+    let len = F::DEGREE.div_ceil(8);
+    let msg = [seed, &(attempt as u64).to_be_bytes()].concat();
+    let bytes = expand_message_xmd::<H>(&msg, GENERATION_DST, 2 * len).expect("two field elements fit into expand_message_xmd");
+    (element_from_bytes(&bytes[..len]), element_from_bytes(&bytes[len..]))
+}
+
+fn element_from_bytes<F: PolynomialBasis>(bytes: &[u8]) -> F {
+    let mut words: Vec<u64> = bytes.rchunks(8).map(|c| c.iter().fold(0, |w, b| w << 8 | *b as u64)).collect();
+    if F::DEGREE % 64 != 0 {
+        *words.last_mut().expect("m > 0") &= (1 << (F::DEGREE % 64)) - 1;
+    }
+    F::from_words(&words).expect("fewer than m bits are set")
+}
+
+/// Tries the candidates `derive_coefficients(seed, 1)`, `derive_coefficients(seed, 2)`, ... until
+/// a curve meets the requirements. As in DSTU 4145-2002 the seed is published with the curve,
+/// anyone can repeat the generation with the same hash function and check that the coefficients
+/// weren't chosen. Candidates the counter can't count are skipped, CountingUnsupported means it
+/// couldn't count any of them.
+pub fn generate_curve<F, C, H>(counter: &C, requirements: &CurveRequirements, seed: &[u8]) -> Result<GeneratedCurve<F>, GenerationError>
+    where F: PolynomialBasis, C: PointCounter<F>, H: HashFunction
+{
+    let mut counted = false;
+    for attempts in 1..=requirements.max_attempts {
+        let (a, b) = derive_coefficients::<F, H>(seed, attempts);
+        if b.is_zero() {
+            continue;
+        }
+
+        let Some(group_order) = counter.count_points(&a, &b) else {
+            continue;
+        };
+        counted = true;
+        if let Some((order, cofactor)) = split_order::<F>(&group_order, requirements) {
+            return Ok(GeneratedCurve { a, b, order, cofactor, attempts });
+        }
+    }
+    Err(if counted { GenerationError::NotFound } else { GenerationError::CountingUnsupported })
+}
+
+// #E = h n with the smallest allowed h leaving a prime n that passes the order checks
+fn split_order<F: BinaryField>(group_order: &UnsignedLongInt, requirements: &CurveRequirements) -> Option<(UnsignedLongInt, UnsignedLongInt)> {
+    (1..=requirements.max_cofactor).find_map(|h| {
+        let cofactor = UnsignedLongInt::from(h);
+        let (order, r) = div_rem(group_order, &cofactor);
+        let suitable = is_zero(&r)
+            && is_probable_prime(&order, requirements.primality_rounds)
+            && check_order_with_mov_bound(F::DEGREE, &order, &cofactor, requirements.mov_bound).is_ok();
+        suitable.then_some((order, cofactor))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::str::FromStr;
+    use crate::agm::AgmCounter;
+    use crate::gf2m::GF2_163;
+    use crate::hash_to_curve::tests::Sha256Hash;
+    use crate::small_field::GF256;
+    use crate::MOD_DEG;
+    use super::*;
+
+    // Tiny fields have a tiny n, so the MOV condition is relaxed
+    fn tiny_requirements() -> CurveRequirements {
+        CurveRequirements { mov_bound: 4, ..CurveRequirements::default() }
+    }
+
+    #[test]
+    fn brute_force_test() {
+        let counter = BruteForceCounter;
+        for (a, b) in [(0, 1), (1, 1), (0x53, 0xca), (1, 0x1f)] {
+            let (a, b) = (GF256::new(a), GF256::new(b));
+            let count = counter.count_points(&a, &b).unwrap();
+
+            // every point counted by hand
+            let mut points = 1u64;
+            for x in 0..256 {
+                for y in 0..256 {
+                    let (x, y) = (GF256::new(x), GF256::new(y));
+                    if y.sqr().add(&x.mul(&y)) == x.sqr().mul(&x).add(&a.mul(&x.sqr())).add(&b) {
+                        points += 1;
+                    }
+                }
+            }
+            assert_eq!(count, UnsignedLongInt::from(points));
+        }
+    }
+
+    #[test]
+    fn derivation_test() -> Result<(), Box<dyn Error>> {
+        // first candidate for the seed "DSTU", computed independently from RFC 9380
+        let (a, b) = derive_coefficients::<GF2_163, Sha256Hash>(b"DSTU", 1);
+        assert_eq!(a, GF2_163::from_str("3024B7D3E5154115017AE75D9B42D34AB8522EED9")?);
+        assert_eq!(b, GF2_163::from_str("2AD62FA707213BA7D36B38F22E13851F7BF0167A2")?);
+
+        assert_ne!(derive_coefficients::<GF2_163, Sha256Hash>(b"DSTU", 2), (a, b));
+        assert_ne!(derive_coefficients::<GF2_163, Sha256Hash>(b"DSTV", 1), (a, b));
+        // the mask keeps m bits
+        let (a, b) = derive_coefficients::<GF2Element<DIM>, Sha256Hash>(b"DSTU", 1);
+        assert!(a.deg() < MOD_DEG && b.deg() < MOD_DEG && a != b);
+
+        Ok(())
+    }
+
+    #[test]
+    fn generation_test() -> Result<(), Box<dyn Error>> {
+        let requirements = tiny_requirements();
+        let curve: GeneratedCurve<GF256> = generate_curve::<_, _, Sha256Hash>(&BruteForceCounter, &requirements, b"seven")?;
+
+        let group_order = BruteForceCounter.count_points(&curve.a, &curve.b).unwrap();
+        assert_eq!(curve.cofactor.mul(&curve.order), group_order);
+        assert!(is_probable_prime(&curve.order, 20));
+        assert!(curve.cofactor <= UnsignedLongInt::from(requirements.max_cofactor));
+
+        // the seed and the number of attempts are enough to check the coefficients
+        assert_eq!(derive_coefficients::<GF256, Sha256Hash>(b"seven", curve.attempts), (curve.a, curve.b));
+        assert_eq!(generate_curve::<_, _, Sha256Hash>(&BruteForceCounter, &requirements, b"seven")?, curve);
+        let other: GeneratedCurve<GF256> = generate_curve::<_, _, Sha256Hash>(&BruteForceCounter, &requirements, b"eight")?;
+        assert_ne!((other.a, other.b), (curve.a, curve.b));
+
+        Ok(())
+    }
+
+    #[test]
+    fn requirements_test() {
+        // #E is even, so a cofactor of 1 is never enough
+        let strict = CurveRequirements { max_cofactor: 1, max_attempts: 50, ..tiny_requirements() };
+        assert_eq!(generate_curve::<GF256, _, Sha256Hash>(&BruteForceCounter, &strict, b"one"), Err(GenerationError::NotFound));
+        // the full MOV bound rules out every curve over GF(2^8)
+        let mov = CurveRequirements { max_attempts: 50, ..CurveRequirements::default() };
+        assert_eq!(generate_curve::<GF256, _, Sha256Hash>(&BruteForceCounter, &mov, b"one"), Err(GenerationError::NotFound));
+    }
+
+    #[test]
+    fn configured_field_test() -> Result<(), Box<dyn Error>> {
+        let order = KoblitzCounter.count_points(&GF2Element::ONE, &GF2Element::ONE).unwrap();
+        assert_eq!(Some(order), Curve::new(GF2Element::ONE, GF2Element::ONE)?.koblitz_group_order().ok());
+        for a in [GF2Element::ZERO, GF2Element::ONE] {
+            assert_eq!(AgmCounter.count_points(&a, &GF2Element::ONE), KoblitzCounter.count_points(&a, &GF2Element::ONE));
+        }
+
+        // KoblitzCounter skips every random candidate, AgmCounter counts them in seconds but too
+        // slowly for a debug build
+        let result = generate_curve::<GF2Element<DIM>, _, Sha256Hash>(&KoblitzCounter, &CurveRequirements::default(), b"");
+        assert_eq!(result, Err(GenerationError::CountingUnsupported));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use vl_big_ints::UnsignedLongInt;
+use crate::field::BinaryField;
+use crate::long_int::{div_rem, mod_mul, mod_pow, mod_sub, to_u64};
+use crate::order::factorization_value;
+
+// Number of precomputed multipliers of the r-adding walk
+const WALK_BRANCHES: usize = 16;
+// Largest prime Pohlig-Hellman hands to baby-step giant-step instead of Pollard rho
+const BSGS_LIMIT: u64 = 1 << 24;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DlogError {
+    ZeroOrder,
+    InvalidFactorization,
+    OrderTooLarge,
+    NotFound,
+}
+
+impl Display for DlogError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ZeroOrder => write!(f, "subgroup order must be positive"),
+            Self::InvalidFactorization => write!(f, "factorization needs primes above 1 with positive exponents"),
+            Self::OrderTooLarge => write!(f, "subgroup order is too large for this method"),
+            Self::NotFound => write!(f, "element is not in the subgroup"),
+        }
+    }
+}
+
+impl Error for DlogError {}
+
+/// x with g^x = h for g of the given order, O(sqrt(n)) time and memory
+pub fn baby_step_giant_step<F>(g: &F, h: &F, order: &UnsignedLongInt) -> Result<UnsignedLongInt, DlogError>
+    where F: BinaryField + Hash + Eq
+{
+    let n = small_order(order)?;
+    let x = bsgs(g, h, n)?;
+
+    verified(g, h, UnsignedLongInt::from(x))
+}
+
+/// x with g^x = h for g of prime order, random r-adding walks ending at distinguished points
+pub fn pollard_rho<F>(g: &F, h: &F, order: &UnsignedLongInt) -> Result<UnsignedLongInt, DlogError>
+    where F: BinaryField + Hash + Eq
+{
+    let n = small_order(order)?;
+    let x = rho(g, h, n)?;
+
+    verified(g, h, UnsignedLongInt::from(x))
+}
+
+/// x with g^x = h given the factorization of the order of g, every prime factor must fit into u64
+pub fn pohlig_hellman<F>(g: &F, h: &F, factorization: &[(UnsignedLongInt, usize)]) -> Result<UnsignedLongInt, DlogError>
+    where F: BinaryField + Hash + Eq
+{
+    // phi(p^e) below needs p^e >= 2
+    if factorization.iter().any(|(p, e)| *e == 0 || p < &UnsignedLongInt::from(2)) {
+        return Err(DlogError::InvalidFactorization);
+    }
+    let n = factorization_value(factorization);
+    let g_inv = g.inverse();
+
+    // x = x_mod (mod modulus), combined with CRT one prime power at a time
+    let mut x_mod = UnsignedLongInt::from(0);
+    let mut modulus = UnsignedLongInt::from(1);
+
+    for (p, e) in factorization {
+        let small_p = small_order(p)?;
+        let (n_over_p, _) = div_rem(&n, p);
+        let gamma = g.pow(&n_over_p);
+
+        // digits of x mod p^e in base p
+        let mut x_p = UnsignedLongInt::from(0);
+        let mut p_k = UnsignedLongInt::from(1);
+        let mut cofactor = n_over_p;
+        for _ in 0..*e {
+            let h_k = h.mul(&g_inv.pow(&x_p)).pow(&cofactor);
+            let d = if small_p <= BSGS_LIMIT {
+                bsgs(&gamma, &h_k, small_p)?
+            } else {
+                rho(&gamma, &h_k, small_p)?
+            };
+
+            x_p = x_p.add(&p_k.mul(&UnsignedLongInt::from(d)));
+            p_k = p_k.mul(p);
+            cofactor = div_rem(&cofactor, p).0;
+        }
+
+        // x = x_mod + modulus * t, t = (x_p - x_mod) / modulus mod p^e,
+        // the inverse comes from Euler's theorem with phi(p^e) = p^(e-1) (p - 1)
+        let phi = div_rem(&p_k, p).0.mul(&p.sub(&UnsignedLongInt::from(1)));
+        let modulus_inv = mod_pow(&modulus, &phi.sub(&UnsignedLongInt::from(1)), &p_k);
+        let diff = mod_sub(&x_p, &div_rem(&x_mod, &p_k).1, &p_k);
+        let t = mod_mul(&diff, &modulus_inv, &p_k);
+
+        x_mod = x_mod.add(&modulus.mul(&t));
+        modulus = modulus.mul(&p_k);
+    }
+
+    verified(g, h, x_mod)
+}
+
+// Orders the u64 based solvers can handle, the reductions mod n need n > 0
+fn small_order(order: &UnsignedLongInt) -> Result<u64, DlogError> {
+    match to_u64(order) {
+        None => Err(DlogError::OrderTooLarge),
+        Some(0) => Err(DlogError::ZeroOrder),
+        Some(n) => Ok(n),
+    }
+}
+
+fn verified<F: BinaryField>(g: &F, h: &F, x: UnsignedLongInt) -> Result<UnsignedLongInt, DlogError> {
+    if &g.pow(&x) == h { Ok(x) } else { Err(DlogError::NotFound) }
+}
+
+fn bsgs<F>(g: &F, h: &F, n: u64) -> Result<u64, DlogError>
+    where F: BinaryField + Hash + Eq
+{
+    let m = isqrt(n) + 1;
+
+    let mut baby = HashMap::with_capacity(m as usize);
+    let mut p = F::one();
+    for j in 0..m {
+        baby.entry(p.clone()).or_insert(j);
+        p = p.mul(g);
+    }
+
+    // g^(-m) without a field inversion
+    let giant = g.pow(&UnsignedLongInt::from((n - m % n) % n));
+    let mut gamma = h.clone();
+    for i in 0..m {
+        if let Some(j) = baby.get(&gamma) {
+            return Ok(((i as u128 * m as u128 + *j as u128) % n as u128) as u64);
+        }
+        gamma = gamma.mul(&giant);
+    }
+
+    Err(DlogError::NotFound)
+}
+
+fn rho<F>(g: &F, h: &F, n: u64) -> Result<u64, DlogError>
+    where F: BinaryField + Hash + Eq
+{
+    if n == 1 || h == &F::one() {
+        return Ok(0);
+    }
+
+    let mut rng = SplitMix64(0x5eed_d106);
+    let pow2 = |a: u64, b: u64| {
+        g.pow(&UnsignedLongInt::from(a)).mul(&h.pow(&UnsignedLongInt::from(b)))
+    };
+
+    let mut steps = Vec::with_capacity(WALK_BRANCHES);
+    for _ in 0..WALK_BRANCHES {
+        let (c, d) = (rng.next() % n, rng.next() % n);
+        steps.push((pow2(c, d), c, d));
+    }
+
+    let sqrt_n = isqrt(n) + 1;
+    let dp_bits = (64 - sqrt_n.leading_zeros()) / 2;
+    let dp_mask = (1u64 << dp_bits) - 1;
+    let max_walk = 20 << dp_bits;
+    let mut budget = 64 * sqrt_n + 1024;
+
+    // distinguished point -> (a, b) with point = g^a h^b
+    let mut seen: HashMap<F, (u64, u64)> = HashMap::new();
+    while budget > 0 {
+        let (mut a, mut b) = (rng.next() % n, rng.next() % n);
+        let mut y = pow2(a, b);
+
+        for _ in 0..max_walk {
+            let hash = hash_of(&y);
+            if hash & dp_mask == 0 {
+                break;
+            }
+            let (m, c, d) = &steps[(hash >> dp_bits) as usize % WALK_BRANCHES];
+            y = y.mul(m);
+            a = ((a as u128 + *c as u128) % n as u128) as u64;
+            b = ((b as u128 + *d as u128) % n as u128) as u64;
+            budget = budget.saturating_sub(1);
+        }
+        if hash_of(&y) & dp_mask != 0 {
+            // walk is stuck in a cycle without distinguished points
+            continue;
+        }
+
+        match seen.get(&y) {
+            Some(&(a2, b2)) if b2 != b => {
+                // a + b x = a2 + b2 x (mod n)
+                let x = mul_mod(sub_mod(a, a2, n), inv_mod(sub_mod(b2, b, n), n), n);
+                return Ok(x);
+            }
+            Some(_) => {}
+            None => {
+                seen.insert(y, (a, b));
+            }
+        }
+    }
+
+    Err(DlogError::NotFound)
+}
+
+fn hash_of<F: Hash>(el: &F) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    el.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn isqrt(n: u64) -> u64 {
+    let mut r = (n as f64).sqrt() as u64;
+    while r * r > n {
+        r -= 1;
+    }
+    while (r + 1) * (r + 1) <= n {
+        r += 1;
+    }
+    r
+}
+
+fn sub_mod(a: u64, b: u64, n: u64) -> u64 {
+    ((a as u128 + n as u128 - b as u128) % n as u128) as u64
+}
+
+fn mul_mod(a: u64, b: u64, n: u64) -> u64 {
+    (a as u128 * b as u128 % n as u128) as u64
+}
+
+// n is prime, so Fermat's little theorem gives the inverse
+fn inv_mod(a: u64, n: u64) -> u64 {
+    let mut result = 1;
+    let mut base = a % n;
+    let mut e = n - 2;
+    while e > 0 {
+        if e & 1 == 1 {
+            result = mul_mod(result, base, n);
+        }
+        base = mul_mod(base, base, n);
+        e >>= 1;
+    }
+    result
+}
+
+// Deterministic source for the walk parameters, so runs are reproducible
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::str::FromStr;
+    use crate::{GF2Element, DIM};
+    use super::*;
+
+    fn subgroup_839() -> GF2Element<DIM> {
+        GF2Element::subgroup_generator(&[(UnsignedLongInt::from(839), 1)]).unwrap()
+    }
+
+    #[test]
+    fn bsgs_test() -> Result<(), Box<dyn Error>> {
+        let g = subgroup_839();
+        let n = UnsignedLongInt::from(839);
+        for x in [0, 1, 838, rand::random::<u64>() % 839] {
+            let h = g.pow(&UnsignedLongInt::from(x));
+            assert_eq!(baby_step_giant_step(&g, &h, &n)?, UnsignedLongInt::from(x));
+        }
+
+        let outside = GF2Element::from_str("050E04B10B1CA453CDA09E9F9EAF055A3F6EE35A162EC9E390CC67888B2FDE0EA1DD5B2A2C9E6E373F3DE18B5621FF810F0C338D71")?;
+        assert_eq!(baby_step_giant_step(&g, &outside, &n), Err(DlogError::NotFound));
+        assert_eq!(baby_step_giant_step(&g, &outside, &UnsignedLongInt::from(0)), Err(DlogError::ZeroOrder));
+        assert_eq!(pollard_rho(&g, &outside, &UnsignedLongInt::from(0)), Err(DlogError::ZeroOrder));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rho_test() -> Result<(), Box<dyn Error>> {
+        let g = subgroup_839();
+        let n = UnsignedLongInt::from(839);
+        for x in [1, rand::random::<u64>() % 839] {
+            let h = g.pow(&UnsignedLongInt::from(x));
+            assert_eq!(pollard_rho(&g, &h, &n)?, UnsignedLongInt::from(x));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn pohlig_hellman_test() -> Result<(), Box<dyn Error>> {
+        let g = subgroup_839();
+        let x = rand::random::<u64>() % 839;
+        let h = g.pow(&UnsignedLongInt::from(x));
+
+        assert_eq!(pohlig_hellman(&g, &h, &[(UnsignedLongInt::from(839), 1)])?, UnsignedLongInt::from(x));
+        assert_eq!(pohlig_hellman(&g, &h, &[(UnsignedLongInt::from(839), 0)]), Err(DlogError::InvalidFactorization));
+        assert_eq!(pohlig_hellman(&g, &h, &[(UnsignedLongInt::from(1), 1), (UnsignedLongInt::from(839), 1)]), Err(DlogError::InvalidFactorization));
+        assert_eq!(pohlig_hellman(&g, &h, &[(UnsignedLongInt::from(0), 1)]), Err(DlogError::InvalidFactorization));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use vl_big_ints::UnsignedLongInt;
+use crate::curve::{Curve, Point};
+use crate::{GF2Element, DIM};
+
+/// Curve with a base point P of order n, #E = h n for the cofactor h
+#[derive(Debug, Clone, PartialEq)]
+pub struct DomainParameters<F = GF2Element<DIM>> {
+    pub curve: Curve<F>,
+    pub base: Point<F>,
+    pub order: UnsignedLongInt,
+    pub cofactor: UnsignedLongInt,
+}
+
+impl<F> DomainParameters<F> {
+    pub fn new(curve: Curve<F>, base: Point<F>, order: UnsignedLongInt, cofactor: UnsignedLongInt) -> Self {
+        Self { curve, base, order, cofactor }
+    }
+}
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::error::Error;
+    use std::str::FromStr;
+    use crate::curve::tests::point_after;
+    use super::*;
+
+    // Candidate 1042 of the seed "gf2 test domain", the first one generate_curve accepts with
+    // AgmCounter, SHA-256 and the default requirements: #E = 4 n with a prime n
+    pub(crate) fn test_domain() -> Result<DomainParameters, Box<dyn Error>> {
+        let curve = Curve::new(
+            GF2Element::from_str("53C29B8796CA8ACC4DC11C85F04F430BB45E3F90C30AE4B9C14C34019DCD7810573D4C8FE840249B7B8E4E13B1807F4A1460E5D55")?,
+            GF2Element::from_str("4F021559E16023E3FBB6DDF3D4D0EBAC8D5F97D49E7C8EDE402BF04F9EB1C000D89FAA736BA8903EA1A33851F232ACD124F09E862")?,
+        )?;
+        let order = UnsignedLongInt::from_str("2000000000000000000000000000000000000000000000000000036E84D34B936FF0F72F07219C259466431619544DF544C7A7F29")?;
+        let base = curve.double(&curve.double(&point_after(&curve, 2)));
+        Ok(DomainParameters::new(curve, base, order, UnsignedLongInt::from(4)))
+    }
+}
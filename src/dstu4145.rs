@@ -0,0 +1,273 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use rand::RngCore;
+use vl_big_ints::UnsignedLongInt;
+use crate::curve::Point;
+use crate::domain::DomainParameters;
+use crate::encoding::EncodingError;
+use crate::long_int::{from_words, is_zero, mod_add, mod_mul, random_below, rem};
+use crate::field::PolynomialBasis;
+
+// Window width of the scalar multiplications
+const WNAF_WIDTH: usize = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    pub r: UnsignedLongInt,
+    pub s: UnsignedLongInt,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SignatureError {
+    InvalidKey,
+    UnusableNonce,
+}
+
+impl Display for SignatureError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidKey => write!(f, "private key must be in [1, n - 1]"),
+            Self::UnusableNonce => write!(f, "nonce gives a zero signature component, pick another one"),
+        }
+    }
+}
+
+impl Error for SignatureError {}
+
+/// Integer with bit i equal to the coefficient at x^i
+pub fn element_to_integer<F: PolynomialBasis>(el: &F) -> UnsignedLongInt {
+    let words: Vec<u64> = el.to_words().into_iter().rev().collect();
+    from_words(&words)
+}
+
+/// Polynomial with the coefficient at x^i equal to bit i, the integer must be below 2^m
+pub fn integer_to_element<F: PolynomialBasis>(k: &UnsignedLongInt) -> Result<F, EncodingError> {
+    if k.get_highest_set_bit().is_some_and(|top| top >= F::DEGREE) {
+        return Err(EncodingError::NotAnElement);
+    }
+
+    let mut words = vec![0u64; F::DEGREE.div_ceil(64)];
+    for i in (0..F::DEGREE).filter(|&i| k.get_bit(i)) {
+        words[i / 64] |= 1 << (i % 64);
+    }
+    F::from_words(&words).ok_or(EncodingError::NotAnElement)
+}
+
+/// Hash value to a field element: the bytes are read as a little-endian number and cut to its m lowest bits,
+/// zero is replaced by one
+pub fn hash_to_element<F: PolynomialBasis>(hash: &[u8]) -> F {
+    let mut words = vec![0u64; F::DEGREE.div_ceil(64)];
+    for (j, byte) in hash.iter().enumerate().take(F::DEGREE.div_ceil(8)) {
+        words[j / 8] |= (*byte as u64) << (8 * (j % 8));
+    }
+    // clear the bits at and above x^m
+    if F::DEGREE % 64 != 0 {
+        words[F::DEGREE / 64] &= (1 << (F::DEGREE % 64)) - 1;
+    }
+
+    let el = F::from_words(&words).expect("the bits above x^m are cleared");
+    if el.is_zero() { F::one() } else { el }
+}
+
+// k mod 2^bits
+fn truncate(k: &UnsignedLongInt, bits: usize) -> UnsignedLongInt {
+    let mut words = vec![0u64; bits.div_ceil(64)];
+    let len = words.len();
+    for i in (0..bits).filter(|&i| k.get_bit(i)) {
+        words[len - 1 - i / 64] |= 1 << (i % 64);
+    }
+    from_words(&words)
+}
+
+impl<F: PolynomialBasis> DomainParameters<F> {
+    /// Random private key d in [1, n - 1] and the public key Q = -dP
+    pub fn dstu_generate_key<R: RngCore>(&self, rng: &mut R) -> (UnsignedLongInt, Point<F>) {
+        let d = random_below(rng, &self.order);
+        let q = self.dstu_public_key(&d);
+        (d, q)
+    }
+
+    pub fn dstu_public_key(&self, d: &UnsignedLongInt) -> Point<F> {
+        self.curve.neg(&self.curve.wnaf_mul(&self.base, d, WNAF_WIDTH))
+    }
+
+    /// Signature of a hash value with fresh random nonces
+    pub fn dstu_sign<R: RngCore>(&self, d: &UnsignedLongInt, hash: &[u8], rng: &mut R) -> Result<Signature, SignatureError> {
+        loop {
+            let e = random_below(rng, &self.order);
+            match self.dstu_sign_with_nonce(d, hash, &e) {
+                Err(SignatureError::UnusableNonce) => continue,
+                result => return result,
+            }
+        }
+    }
+
+    /// DSTU 4145-2002 signing with the given nonce e in [1, n - 1]:
+    /// F = x(eP), r = the L(n) - 1 lowest bits of h F as an integer, s = e + d r mod n
+    pub fn dstu_sign_with_nonce(&self, d: &UnsignedLongInt, hash: &[u8], e: &UnsignedLongInt) -> Result<Signature, SignatureError> {
+        if is_zero(d) || d >= &self.order {
+            return Err(SignatureError::InvalidKey);
+        }
+        if is_zero(e) || e >= &self.order {
+            return Err(SignatureError::UnusableNonce);
+        }
+
+        let h: F = hash_to_element(hash);
+        let f = match self.curve.wnaf_mul(&self.base, e, WNAF_WIDTH) {
+            Point::Affine { x, .. } if !x.is_zero() => x,
+            _ => return Err(SignatureError::UnusableNonce),
+        };
+
+        let r = self.element_to_r(&h.mul(&f));
+        if is_zero(&r) {
+            return Err(SignatureError::UnusableNonce);
+        }
+
+        let s = mod_add(&rem(e, &self.order), &mod_mul(d, &r, &self.order), &self.order);
+        if is_zero(&s) {
+            return Err(SignatureError::UnusableNonce);
+        }
+        Ok(Signature { r, s })
+    }
+
+    /// Accepts when 0 < r, s < n and r equals the truncated h x(sP + rQ)
+    pub fn dstu_verify(&self, q: &Point<F>, hash: &[u8], signature: &Signature) -> bool {
+        let Signature { r, s } = signature;
+        if is_zero(r) || r >= &self.order || is_zero(s) || s >= &self.order {
+            return false;
+        }
+
+        let h: F = hash_to_element(hash);
+        match self.curve.shamir_mul(&self.base, s, q, r) {
+            Point::Infinity => false,
+            Point::Affine { x, .. } => &self.element_to_r(&h.mul(&x)) == r,
+        }
+    }
+
+    // Integer of the element cut to L(n) - 1 bits, so it is below n
+    fn element_to_r(&self, y: &F) -> UnsignedLongInt {
+        let bits = self.order.get_highest_set_bit().expect("order is positive");
+        truncate(&element_to_integer(y), bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::str::FromStr;
+    use crate::curve::Curve;
+    use crate::domain::tests::test_domain;
+    use crate::gf2m::GF2_163;
+    use crate::{GF2Element, DIM, MOD_DEG};
+    use super::*;
+
+    // The curve of the signature example in the annex of DSTU 4145-2002, over GF(2^163)
+    fn annex_domain() -> Result<DomainParameters<GF2_163>, Box<dyn Error>> {
+        let curve = Curve::new(GF2_163::ONE, GF2_163::from_str("5FF6108462A2DC8210AB403925E638A19C1455D21")?)?;
+        let base = curve.point(
+            GF2_163::from_str("72D867F93A93AC27DF9FF01AFFE74885C8C540420")?,
+            GF2_163::from_str("0224A9C3947852B97C5599D5F4AB81122ADC3FD9B")?,
+        )?;
+        let order = int("400000000000000000002BEC12BE2262D39BCF14D");
+        Ok(DomainParameters::new(curve, base, order, UnsignedLongInt::from(2)))
+    }
+
+    // hex constant of the annex
+    fn int(hex: &str) -> UnsignedLongInt {
+        UnsignedLongInt::from_str(hex).unwrap()
+    }
+
+    fn hex_bytes(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn conversion_test() -> Result<(), Box<dyn Error>> {
+        let el = GF2Element::from([0x1234_5678_9abc_def0; 2 * DIM]);
+        let k = element_to_integer(&el);
+
+        assert_eq!(integer_to_element::<GF2Element<DIM>>(&k)?, el);
+        assert_eq!(integer_to_element::<GF2Element<DIM>>(&UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(MOD_DEG as u64))), Err(EncodingError::NotAnElement));
+        assert_eq!(truncate(&k, 16), UnsignedLongInt::from(el.data[0] as u64 & 0xffff));
+
+        assert_eq!(hash_to_element::<GF2Element<DIM>>(&[0x02, 0x01]).data[0], 0x0102);
+        assert_eq!(hash_to_element::<GF2Element<DIM>>(&[0; 32]), GF2Element::ONE);
+        // a 512-bit hash keeps its m lowest bits
+        let long: GF2Element<DIM> = hash_to_element(&[0xff; 64]);
+        assert_eq!(long.deg(), MOD_DEG - 1);
+        assert_eq!(long.data[0], usize::MAX);
+
+        Ok(())
+    }
+
+    #[test]
+    fn annex_test() -> Result<(), Box<dyn Error>> {
+        let domain = annex_domain()?;
+        let d = int("183F60FDF7951FF47D67193F8D073790C1C9B5A3E");
+        let e = int("1025E40BD97DB012B7A1D79DE8E12932D247F61C6");
+        // H is the big-endian hash value of the annex, the element reads the bytes little-endian
+        let mut hash = hex_bytes("09C9C44277910C9AAEE486883A2EB95B7180166DDF73532EEB76EDAEF52247FF");
+        hash.reverse();
+
+        assert_eq!(domain.curve.ld_mul(&domain.base, &domain.order), Point::Infinity);
+        assert_eq!(hash_to_element::<GF2_163>(&hash), GF2_163::from_str("3A2EB95B7180166DDF73532EEB76EDAEF52247FF")?);
+
+        let q = domain.dstu_public_key(&d);
+        assert_eq!(q, domain.curve.point(
+            GF2_163::from_str("57DE7FDE023FF929CB6AC785CE4B79CF64ABDC2DA")?,
+            GF2_163::from_str("3E85444324BCF06AD85ABF6AD7B5F34770532B9AA")?,
+        )?);
+
+        let signature = domain.dstu_sign_with_nonce(&d, &hash, &e)?;
+        assert_eq!(domain.curve.wnaf_mul(&domain.base, &e, WNAF_WIDTH).x(), Some(&GF2_163::from_str("42A7D756D70E1C9BA62D2CB43707C35204EF3C67C")?));
+        assert_eq!(signature, Signature {
+            r: int("274EA2C0CAA014A0D80A424F59ADE7A93068D08A7"),
+            s: int("2100D86957331832B8E8C230F5BD6A332B3615ACA"),
+        });
+        assert!(domain.dstu_verify(&q, &hash, &signature));
+        assert!(!domain.dstu_verify(&domain.curve.neg(&q), &hash, &signature));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sign_verify_test() -> Result<(), Box<dyn Error>> {
+        let domain = test_domain()?;
+        let mut rng = rand::thread_rng();
+        let hash: Vec<u8> = (0..32).map(|_| rand::random()).collect();
+
+        let (d, q) = domain.dstu_generate_key(&mut rng);
+        assert!(domain.curve.is_on_curve(&q));
+        let signature = domain.dstu_sign(&d, &hash, &mut rng)?;
+        assert!(domain.dstu_verify(&q, &hash, &signature));
+
+        let mut other_hash = hash.clone();
+        other_hash[0] ^= 1;
+        assert!(!domain.dstu_verify(&q, &other_hash, &signature));
+
+        let forged = Signature { r: signature.r.clone(), s: mod_add(&signature.s, &UnsignedLongInt::from(1), &domain.order) };
+        assert!(!domain.dstu_verify(&q, &hash, &forged));
+
+        Ok(())
+    }
+
+    #[test]
+    fn nonce_test() -> Result<(), Box<dyn Error>> {
+        let domain = test_domain()?;
+        let d = UnsignedLongInt::from(123_456_789);
+        let e = UnsignedLongInt::from(987_654_321);
+        let hash = [0x5a; 32];
+
+        // r is h x(eP) cut below n and s = e + d r mod n
+        let signature = domain.dstu_sign_with_nonce(&d, &hash, &e)?;
+        let f = domain.curve.ld_mul(&domain.base, &e);
+        let y = hash_to_element::<GF2Element<DIM>>(&hash).mul(f.x().unwrap());
+        assert_eq!(signature.r, truncate(&element_to_integer(&y), domain.order.get_highest_set_bit().unwrap()));
+        assert_eq!(signature.s, rem(&e.add(&d.mul(&signature.r)), &domain.order));
+
+        assert_eq!(domain.dstu_sign_with_nonce(&domain.order, &hash, &e), Err(SignatureError::InvalidKey));
+        assert_eq!(domain.dstu_sign_with_nonce(&d, &hash, &UnsignedLongInt::from(0)), Err(SignatureError::UnusableNonce));
+
+        Ok(())
+    }
+}
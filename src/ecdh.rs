@@ -0,0 +1,134 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use rand::RngCore;
+use vl_big_ints::UnsignedLongInt;
+use crate::curve::Point;
+use crate::domain::DomainParameters;
+use crate::encoding::element_to_bytes;
+use crate::long_int::{is_zero, random_below};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EcdhError {
+    InvalidKey,
+    Infinity,
+    NotOnCurve,
+    WrongSubgroup,
+    InfiniteSharedPoint,
+}
+
+impl Display for EcdhError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidKey => write!(f, "private key must be in [1, n - 1]"),
+            Self::Infinity => write!(f, "peer public key is the point at infinity"),
+            Self::NotOnCurve => write!(f, "peer public key doesn't lie on the curve"),
+            Self::WrongSubgroup => write!(f, "peer public key is not in the subgroup of order n"),
+            Self::InfiniteSharedPoint => write!(f, "shared point is the point at infinity"),
+        }
+    }
+}
+
+impl Error for EcdhError {}
+
+impl DomainParameters {
+    /// Random private key d in [1, n - 1] and the public key dP
+    pub fn ecdh_generate_key<R: RngCore>(&self, rng: &mut R) -> (UnsignedLongInt, Point) {
+        let d = random_below(rng, &self.order);
+        let q = self.curve.ladder_mul(&self.base, &d);
+        (d, q)
+    }
+
+    /// On the curve and not the point at infinity, enough for the cofactor variant
+    pub fn ecdh_partial_validate(&self, q: &Point) -> Result<(), EcdhError> {
+        if q.is_infinity() {
+            return Err(EcdhError::Infinity);
+        }
+        if !self.curve.is_on_curve(q) {
+            return Err(EcdhError::NotOnCurve);
+        }
+        Ok(())
+    }
+
+    /// Partial validation plus nQ = O
+    pub fn ecdh_validate(&self, q: &Point) -> Result<(), EcdhError> {
+        self.ecdh_partial_validate(q)?;
+        if !self.curve.ld_mul(q, &self.order).is_infinity() {
+            return Err(EcdhError::WrongSubgroup);
+        }
+        Ok(())
+    }
+
+    /// x(dQ) as ELEMENT_BYTES big-endian bytes after full validation of Q
+    pub fn ecdh(&self, d: &UnsignedLongInt, peer: &Point) -> Result<Vec<u8>, EcdhError> {
+        self.check_private_key(d)?;
+        self.ecdh_validate(peer)?;
+        self.shared_x(d, peer)
+    }
+
+    /// x(h d Q), the cofactor h removes any small order component of Q, so partial validation suffices
+    pub fn ecdh_cofactor(&self, d: &UnsignedLongInt, peer: &Point) -> Result<Vec<u8>, EcdhError> {
+        self.check_private_key(d)?;
+        self.ecdh_partial_validate(peer)?;
+        self.shared_x(&self.cofactor.mul(d), peer)
+    }
+
+    fn check_private_key(&self, d: &UnsignedLongInt) -> Result<(), EcdhError> {
+        if is_zero(d) || d >= &self.order {
+            return Err(EcdhError::InvalidKey);
+        }
+        Ok(())
+    }
+
+    // The x-only ladder runs the same steps for every scalar
+    fn shared_x(&self, k: &UnsignedLongInt, peer: &Point) -> Result<Vec<u8>, EcdhError> {
+        let x = peer.x().ok_or(EcdhError::Infinity)?;
+        let shared = self.curve.ladder_x(x, k).ok_or(EcdhError::InfiniteSharedPoint)?;
+        Ok(element_to_bytes(&shared))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::domain::tests::test_domain;
+    use crate::encoding::ELEMENT_BYTES;
+    use crate::{GF2Element, MOD_DEG};
+    use super::*;
+
+    #[test]
+    fn agreement_test() -> Result<(), Box<dyn Error>> {
+        let domain = test_domain()?;
+        let mut rng = rand::thread_rng();
+        let (d_a, q_a) = domain.ecdh_generate_key(&mut rng);
+        let (d_b, q_b) = domain.ecdh_generate_key(&mut rng);
+
+        let shared = domain.ecdh(&d_a, &q_b)?;
+        assert_eq!(shared.len(), ELEMENT_BYTES);
+        assert_eq!(shared, domain.ecdh(&d_b, &q_a)?);
+        assert_eq!(domain.ecdh_cofactor(&d_a, &q_b)?, domain.ecdh_cofactor(&d_b, &q_a)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn validation_test() -> Result<(), Box<dyn Error>> {
+        let domain = test_domain()?;
+        let d = UnsignedLongInt::from(0xdead_beef);
+
+        // adding the point of order 2 leaves the subgroup of odd order n
+        let t = domain.curve.point(GF2Element::ZERO, domain.curve.b().frobenius(MOD_DEG - 1))?;
+        let outside = domain.curve.add(&domain.base, &t);
+
+        assert_eq!(domain.ecdh(&d, &outside), Err(EcdhError::WrongSubgroup));
+        assert_eq!(domain.ecdh_cofactor(&d, &outside)?, domain.ecdh_cofactor(&d, &domain.base)?);
+        assert_eq!(domain.ecdh_cofactor(&d, &t), Err(EcdhError::InfiniteSharedPoint));
+        assert_eq!(domain.ecdh(&d, &Point::Infinity), Err(EcdhError::Infinity));
+        assert_eq!(
+            domain.ecdh(&d, &Point::Affine { x: GF2Element::ONE, y: GF2Element::ONE }),
+            Err(EcdhError::NotOnCurve)
+        );
+        assert_eq!(domain.ecdh(&domain.order, &domain.base), Err(EcdhError::InvalidKey));
+
+        Ok(())
+    }
+}
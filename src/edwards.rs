@@ -0,0 +1,409 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use vl_big_ints::UnsignedLongInt;
+use crate::curve::{Curve, Point};
+use crate::ladder::{conditional_swap_elements, LADDER_BITS};
+use crate::solve_sq_eq::halftrace;
+use crate::{GF2Element, DIM, MOD_DEG};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EdwardsError {
+    Singular,
+    NotComplete,
+    NotOnCurve,
+}
+
+impl Display for EdwardsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Singular => write!(f, "binary Edwards curve needs d1 != 0 and d2 != d1^2 + d1"),
+            Self::NotComplete => write!(f, "addition law is complete only for Tr(d2) = 1"),
+            Self::NotOnCurve => write!(f, "point doesn't lie on the curve"),
+        }
+    }
+}
+
+impl Error for EdwardsError {}
+
+/// Complete binary Edwards curve d1 (x + y) + d2 (x^2 + y^2) = xy + xy (x + y) + x^2 y^2
+/// of Bernstein, Lange and Farashahi, with Tr(d2) = 1 the addition law has no exceptions
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EdwardsCurve {
+    d1: GF2Element<DIM>,
+    d2: GF2Element<DIM>,
+}
+
+/// Affine point, the neutral element is (0, 0) and -(x, y) = (y, x)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EdwardsPoint {
+    pub x: GF2Element<DIM>,
+    pub y: GF2Element<DIM>,
+}
+
+impl EdwardsPoint {
+    pub const NEUTRAL: Self = Self { x: GF2Element::ZERO, y: GF2Element::ZERO };
+}
+
+// Projective (X : Y : Z) for the affine point (X / Z, Y / Z)
+type Xyz = (GF2Element<DIM>, GF2Element<DIM>, GF2Element<DIM>);
+
+// Projective (W : Z) for w = x + y, which P, -P, P + (1, 1) and -P + (1, 1) share
+type WzPair = (GF2Element<DIM>, GF2Element<DIM>);
+
+impl EdwardsCurve {
+    pub fn new(d1: GF2Element<DIM>, d2: GF2Element<DIM>) -> Result<Self, EdwardsError> {
+        if d1 == GF2Element::ZERO || d2 == d1.sqr().add(&d1) {
+            return Err(EdwardsError::Singular);
+        }
+        if d2.trace() != GF2Element::ONE {
+            return Err(EdwardsError::NotComplete);
+        }
+        Ok(Self { d1, d2 })
+    }
+
+    pub fn d1(&self) -> &GF2Element<DIM> {
+        &self.d1
+    }
+
+    pub fn d2(&self) -> &GF2Element<DIM> {
+        &self.d2
+    }
+
+    pub fn is_on_curve(&self, p: &EdwardsPoint) -> bool {
+        let (x, y) = (&p.x, &p.y);
+        let s = x.add(y);
+        let xy = x.mul(y);
+        self.d1.mul(&s).add(&self.d2.mul(&s.sqr())) == xy.add(&xy.mul(&s)).add(&xy.sqr())
+    }
+
+    pub fn point(&self, x: GF2Element<DIM>, y: GF2Element<DIM>) -> Result<EdwardsPoint, EdwardsError> {
+        let p = EdwardsPoint { x, y };
+        if !self.is_on_curve(&p) {
+            return Err(EdwardsError::NotOnCurve);
+        }
+        Ok(p)
+    }
+
+    pub fn neg(&self, p: &EdwardsPoint) -> EdwardsPoint {
+        EdwardsPoint { x: p.y.clone(), y: p.x.clone() }
+    }
+
+    /// The same formula for every pair of points, doubling and the neutral element included
+    pub fn add(&self, p: &EdwardsPoint, q: &EdwardsPoint) -> EdwardsPoint {
+        self.to_affine(&self.add_projective(&to_projective(p), &to_projective(q)))
+    }
+
+    pub fn double(&self, p: &EdwardsPoint) -> EdwardsPoint {
+        self.to_affine(&self.double_projective(&to_projective(p)))
+    }
+
+    /// kP with a Montgomery ladder on the complete projective formulas, the same operations
+    /// for every scalar below 2^(m+1) and one inversion at the end
+    pub fn mul(&self, p: &EdwardsPoint, k: &UnsignedLongInt) -> EdwardsPoint {
+        let mut r0 = to_projective(&EdwardsPoint::NEUTRAL);
+        let mut r1 = to_projective(p);
+
+        let bits = k.get_highest_set_bit().map_or(0, |top| top + 1).max(LADDER_BITS);
+        for i in (0..bits).rev() {
+            let bit = k.get_bit(i);
+            conditional_swap(&mut r0, &mut r1, bit);
+            r1 = self.add_projective(&r0, &r1);
+            r0 = self.double_projective(&r0);
+            conditional_swap(&mut r0, &mut r1, bit);
+        }
+        self.to_affine(&r0)
+    }
+
+    /// w(kP) from w = w(P) = x + y alone with the differential formulas
+    /// w(2P) = (w^2 + w^4) / (d1 + w^2 + d2 / d1 w^4) and
+    /// w(P + Q) = w(P - Q) + A / (d1 + A + (1 + d2 / d1) w(P)^2 w(Q)^2), A = w(P) w(Q) (1 + w(P)) (1 + w(Q)).
+    /// The denominators don't vanish on a complete curve.
+    pub fn ladder_w(&self, w: &GF2Element<DIM>, k: &UnsignedLongInt) -> GF2Element<DIM> {
+        let mut r0 = (GF2Element::ZERO, GF2Element::ONE);
+        let mut r1 = (w.clone(), GF2Element::ONE);
+
+        let bits = k.get_highest_set_bit().map_or(0, |top| top + 1).max(LADDER_BITS);
+        for i in (0..bits).rev() {
+            let bit = k.get_bit(i);
+            conditional_swap_elements(&mut r0.0, &mut r1.0, bit);
+            conditional_swap_elements(&mut r0.1, &mut r1.1, bit);
+            r1 = self.w_add(w, &r0, &r1);
+            r0 = self.w_double(&r0);
+            conditional_swap_elements(&mut r0.0, &mut r1.0, bit);
+            conditional_swap_elements(&mut r0.1, &mut r1.1, bit);
+        }
+        r0.0.mul(&r0.1.inverse())
+    }
+
+    // The addition law of BLF over the common denominator Z1^3 Z2^2:
+    // x3 = (d1 (x1 + x2) + d2 (x1 + y1)(x2 + y2) + (x1 + x1^2)(x2 (y1 + y2 + 1) + y1 y2)) / (d1 + (x1 + x1^2)(x2 + y2))
+    // and y3 the same with x and y swapped
+    fn add_projective(&self, (x1, y1, z1): &Xyz, (x2, y2, z2): &Xyz) -> Xyz {
+        let z1z2 = z1.mul(z2);
+        let d1z = self.d1.mul(&z1.mul(&z1z2));
+        let a1 = x1.mul(z1).add(&x1.sqr());
+        let b1 = y1.mul(z1).add(&y1.sqr());
+        let s2 = x2.add(y2);
+        let g = self.d2.mul(&z1.mul(&z1z2)).mul(&x1.add(y1).mul(&s2));
+
+        let x1z2 = x1.mul(z2);
+        let x2z1 = x2.mul(z1);
+        let y1z2 = y1.mul(z2);
+        let y2z1 = y2.mul(z1);
+        let nx = d1z.mul(&x1z2.add(&x2z1)).add(&g)
+            .add(&a1.mul(&x2.mul(&y1z2.add(&y2z1).add(&z1z2)).add(&y1.mul(y2).mul(z2))));
+        let ny = d1z.mul(&y1z2.add(&y2z1)).add(&g)
+            .add(&b1.mul(&y2.mul(&x1z2.add(&x2z1).add(&z1z2)).add(&x1.mul(x2).mul(z2))));
+        let dx = d1z.add(&a1.mul(&s2));
+        let dy = d1z.add(&b1.mul(&s2));
+
+        (nx.mul(&dy), ny.mul(&dx), z1z2.mul(&dx).mul(&dy))
+    }
+
+    // The addition law for P = Q over the common denominator Z^4:
+    // x3 = (d2 (x + y)^2 + (x + x^2)(x + y^2)) / (d1 + (x + x^2)(x + y)), y3 symmetric
+    fn double_projective(&self, (x, y, z): &Xyz) -> Xyz {
+        let x2 = x.sqr();
+        let y2 = y.sqr();
+        let z2 = z.sqr();
+        let xz = x.mul(z);
+        let yz = y.mul(z);
+        let s = x.add(y);
+        let g = self.d2.mul(&s.sqr()).mul(&z2);
+        let d1z = self.d1.mul(&z2.sqr());
+        let a = xz.add(&x2);
+        let b = yz.add(&y2);
+        let sz = s.mul(z);
+
+        let nx = g.add(&a.mul(&xz.add(&y2)));
+        let ny = g.add(&b.mul(&yz.add(&x2)));
+        let dx = d1z.add(&a.mul(&sz));
+        let dy = d1z.add(&b.mul(&sz));
+
+        (nx.mul(&dy), ny.mul(&dx), dx.mul(&dy))
+    }
+
+    // (d1 W^2 (W + Z)^2 : d1^2 Z^4 + d1 W^2 Z^2 + d2 W^4)
+    fn w_double(&self, (w, z): &WzPair) -> WzPair {
+        let w2 = w.sqr();
+        let z2 = z.sqr();
+        let w2z2 = w2.mul(&z2);
+        let wz = w2.add(&z2).mul(&w2);
+        let d1_2 = self.d1.sqr();
+        (self.d1.mul(&wz), d1_2.mul(&z2.sqr()).add(&self.d1.mul(&w2z2)).add(&self.d2.mul(&w2.sqr())))
+    }
+
+    // Differential addition with the affine w0 of the difference, scaled by d1 to avoid d2 / d1
+    fn w_add(&self, w0: &GF2Element<DIM>, (w1, z1): &WzPair, (w2, z2): &WzPair) -> WzPair {
+        let w1w2 = w1.mul(w2);
+        let a = w1w2.mul(&w1.add(z1).mul(&w2.add(z2)));
+        let z1z2 = z1.mul(z2);
+        let da = self.d1.mul(&a);
+        let b = self.d1.sqr().mul(&z1z2.sqr()).add(&da).add(&self.d1.add(&self.d2).mul(&w1w2.sqr()));
+        (w0.mul(&b).add(&da), b)
+    }
+
+    fn to_affine(&self, (x, y, z): &Xyz) -> EdwardsPoint {
+        let z_inv = z.inverse();
+        EdwardsPoint { x: x.mul(&z_inv), y: y.mul(&z_inv) }
+    }
+}
+
+fn to_projective(p: &EdwardsPoint) -> Xyz {
+    (p.x.clone(), p.y.clone(), GF2Element::ONE)
+}
+
+fn conditional_swap(p: &mut Xyz, q: &mut Xyz, swap: bool) {
+    conditional_swap_elements(&mut p.0, &mut q.0, swap);
+    conditional_swap_elements(&mut p.1, &mut q.1, swap);
+    conditional_swap_elements(&mut p.2, &mut q.2, swap);
+}
+
+/// Birational equivalence between a curve y^2 + xy = x^3 + a x^2 + b and a complete binary Edwards curve.
+/// The Edwards curve is equivalent to v^2 + uv = u^3 + (d1^2 + d2) u^2 + d1^4 (d1^4 + d1^2 + d2^2),
+/// which turns into the given curve by (u, v) -> (u, v + s u) with s^2 + s = a + d1^2 + d2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BirationalMap {
+    edwards: EdwardsCurve,
+    curve: Curve,
+    s: GF2Element<DIM>,
+}
+
+impl BirationalMap {
+    /// The Edwards curve together with its own Weierstrass model
+    pub fn from_edwards(edwards: &EdwardsCurve) -> Self {
+        let (d1, d2) = (&edwards.d1, &edwards.d2);
+        let d1_2 = d1.sqr();
+        let a = d1_2.add(d2);
+        let b = d1_2.sqr().mul(&d1_2.sqr().add(&d1_2).add(&d2.sqr()));
+        let curve = Curve::new(a, b).expect("b = d1^4 (d1^4 + d1^2 + d2^2) != 0 for a non-singular Edwards curve");
+        Self { edwards: edwards.clone(), curve, s: GF2Element::ZERO }
+    }
+
+    /// Complete Edwards form of any curve over GF(2^m), m >= 3: the smallest d1 with
+    /// Tr(d1) = Tr(a) + 1 and Tr(b^(1/4) / d1) = 1, then d2 = d1^2 + d1 + sqrt(b) / d1^2 has trace 1
+    pub fn for_curve(curve: &Curve) -> Self {
+        let b_4 = curve.b().frobenius(MOD_DEG - 2);
+        let wanted = curve.a().trace().add(&GF2Element::ONE);
+
+        let (d1, d1_inv) = (1..).map(|v| {
+            let mut d1 = GF2Element::ZERO;
+            d1.data[0] = v;
+            d1
+        }).filter(|d1| d1.trace() == wanted)
+            .map(|d1| {
+                let inv = d1.inverse();
+                (d1, inv)
+            })
+            .find(|(_, inv)| b_4.mul(inv).trace() == GF2Element::ONE)
+            .expect("a suitable d1 exists for m >= 3");
+
+        // sqrt(b) / d1^2 = (b^(1/4) / d1)^2
+        let d2 = d1.sqr().add(&d1).add(&b_4.mul(&d1_inv).sqr());
+        let edwards = EdwardsCurve::new(d1, d2).expect("d1 and d2 are chosen for a complete curve");
+        let s = halftrace(&curve.a().add(&edwards.d1.sqr()).add(&edwards.d2));
+        Self { edwards, curve: curve.clone(), s }
+    }
+
+    pub fn edwards(&self) -> &EdwardsCurve {
+        &self.edwards
+    }
+
+    pub fn curve(&self) -> &Curve {
+        &self.curve
+    }
+
+    /// (u, v) -> (d1 (u + c) / (u + v + (d1^2 + d1) c), d1 (u + c) / (v + (d1^2 + d1) c)), c = d1^2 + d1 + d2,
+    /// for v on the model; the point at infinity goes to (0, 0)
+    pub fn to_edwards(&self, p: &Point) -> EdwardsPoint {
+        let (u, v) = match p {
+            Point::Infinity => return EdwardsPoint::NEUTRAL,
+            Point::Affine { x, y } => (x, y.add(&self.s.mul(x))),
+        };
+        let (d1, d2) = (&self.edwards.d1, &self.edwards.d2);
+        let c = d1.sqr().add(d1).add(d2);
+        let k = d1.sqr().add(d1).mul(&c);
+
+        if u == &c {
+            // the two points of order 4 with a zero coordinate
+            let t = d1.mul(&d2.inverse());
+            return if v == k {
+                EdwardsPoint { x: GF2Element::ZERO, y: t }
+            } else {
+                EdwardsPoint { x: t, y: GF2Element::ZERO }
+            };
+        }
+
+        let n = d1.mul(&u.add(&c));
+        let dx = u.add(&v).add(&k);
+        let dy = v.add(&k);
+        let inv = dx.mul(&dy).inverse();
+        EdwardsPoint { x: n.mul(&dy).mul(&inv), y: n.mul(&dx).mul(&inv) }
+    }
+
+    /// (x, y) -> (u, v) with u = d1 c (x + y) / (xy + d1 (x + y)) and v = d1 c (x / (xy + d1 (x + y)) + d1 + 1),
+    /// (0, 0) goes to the point at infinity
+    pub fn to_weierstrass(&self, p: &EdwardsPoint) -> Point {
+        let (x, y) = (&p.x, &p.y);
+        let (d1, d2) = (&self.edwards.d1, &self.edwards.d2);
+        let s = x.add(y);
+        let den = x.mul(y).add(&d1.mul(&s));
+        if den == GF2Element::ZERO {
+            return Point::Infinity;
+        }
+
+        let k = d1.mul(&d1.sqr().add(d1).add(d2));
+        let inv = den.inverse();
+        let u = k.mul(&s).mul(&inv);
+        let v = k.mul(&x.mul(&inv).add(d1).add(&GF2Element::ONE));
+        Point::Affine { y: v.add(&self.s.mul(&u)), x: u }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::curve::tests::{point_after, test_curve};
+    use crate::long_int::random_below;
+    use super::*;
+
+    #[test]
+    fn map_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let map = BirationalMap::for_curve(&curve);
+        let edwards = map.edwards();
+        assert_eq!(edwards.d2().trace(), GF2Element::ONE);
+
+        let p = point_after(&curve, 2);
+        let q = point_after(&curve, p.x().unwrap().data[0] + 1);
+        let (ep, eq) = (map.to_edwards(&p), map.to_edwards(&q));
+        assert!(edwards.is_on_curve(&ep) && edwards.is_on_curve(&eq));
+        assert_eq!(map.to_weierstrass(&ep), p);
+
+        // the map is a group isomorphism
+        assert_eq!(map.to_edwards(&curve.add(&p, &q)), edwards.add(&ep, &eq));
+        assert_eq!(map.to_edwards(&curve.double(&p)), edwards.double(&ep));
+        assert_eq!(map.to_edwards(&curve.neg(&p)), edwards.neg(&ep));
+        assert_eq!(map.to_edwards(&Point::Infinity), EdwardsPoint::NEUTRAL);
+        assert_eq!(map.to_weierstrass(&EdwardsPoint::NEUTRAL), Point::Infinity);
+
+        // (1, 1) has order 2 and corresponds to (0, sqrt(b))
+        let t = EdwardsPoint { x: GF2Element::ONE, y: GF2Element::ONE };
+        assert_eq!(map.to_weierstrass(&t), curve.point(GF2Element::ZERO, curve.b().frobenius(MOD_DEG - 1))?);
+
+        // the model of the Edwards curve itself
+        let own = BirationalMap::from_edwards(edwards);
+        assert_eq!(own.to_edwards(&own.to_weierstrass(&ep)), ep);
+
+        Ok(())
+    }
+
+    #[test]
+    fn complete_addition_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let map = BirationalMap::for_curve(&curve);
+        let edwards = map.edwards();
+        let p = map.to_edwards(&point_after(&curve, 2));
+        let t = EdwardsPoint { x: GF2Element::ONE, y: GF2Element::ONE };
+
+        // no special cases: the neutral element, inverses, doubling and points of small order
+        assert_eq!(edwards.add(&p, &EdwardsPoint::NEUTRAL), p);
+        assert_eq!(edwards.add(&p, &edwards.neg(&p)), EdwardsPoint::NEUTRAL);
+        assert_eq!(edwards.add(&p, &p), edwards.double(&p));
+        assert_eq!(edwards.add(&t, &t), EdwardsPoint::NEUTRAL);
+        assert_eq!(edwards.add(&p, &t), EdwardsPoint { x: p.x.add(&GF2Element::ONE), y: p.y.add(&GF2Element::ONE) });
+        assert_eq!(edwards.double(&EdwardsPoint::NEUTRAL), EdwardsPoint::NEUTRAL);
+
+        // (0, d1 / d2) and (d1 / d2, 0) are the special cases of the map
+        let t0 = EdwardsPoint { x: GF2Element::ZERO, y: edwards.d1().mul(&edwards.d2().inverse()) };
+        assert!(edwards.is_on_curve(&t0));
+        assert_eq!(map.to_edwards(&map.to_weierstrass(&t0)), t0);
+        assert_eq!(map.to_edwards(&map.to_weierstrass(&edwards.neg(&t0))), edwards.neg(&t0));
+        assert_eq!(map.to_edwards(&curve.double(&map.to_weierstrass(&t0))), edwards.double(&t0));
+
+        assert_eq!(EdwardsCurve::new(GF2Element::ZERO, GF2Element::ONE), Err(EdwardsError::Singular));
+        assert_eq!(EdwardsCurve::new(GF2Element::ONE, GF2Element::ZERO), Err(EdwardsError::Singular));
+
+        Ok(())
+    }
+
+    #[test]
+    fn ladder_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let map = BirationalMap::for_curve(&curve);
+        let edwards = map.edwards();
+        let p = point_after(&curve, 2);
+        let ep = map.to_edwards(&p);
+
+        // a scalar of the full ladder length, the ladders cost the same for every k
+        let k = random_below(&mut rand::thread_rng(), &UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(MOD_DEG as u64)));
+        let kp = map.to_edwards(&curve.ld_mul(&p, &k));
+        assert_eq!(edwards.mul(&ep, &k), kp);
+
+        for (k, kp) in [(UnsignedLongInt::from(0), EdwardsPoint::NEUTRAL), (UnsignedLongInt::from(1), ep.clone()), (k, kp)] {
+            assert_eq!(edwards.ladder_w(&ep.x.add(&ep.y), &k), kp.x.add(&kp.y));
+        }
+
+        Ok(())
+    }
+}
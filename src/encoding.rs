@@ -0,0 +1,278 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::curve::{Curve, Point};
+use crate::solve_sq_eq::halftrace;
+use crate::{GF2Element, DIM, MOD_DEG};
+
+/// Octets in an encoded field element
+pub const ELEMENT_BYTES: usize = MOD_DEG.div_ceil(8);
+
+const WORD: usize = usize::BITS as usize;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EncodingError {
+    InvalidLength,
+    InvalidPrefix,
+    NotAnElement,
+    NoPointWithX,
+    NotOnCurve,
+    Infinity,
+}
+
+impl Display for EncodingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLength => write!(f, "encoding has a wrong length"),
+            Self::InvalidPrefix => write!(f, "unknown point encoding prefix"),
+            Self::NotAnElement => write!(f, "value has bits above the field degree"),
+            Self::NoPointWithX => write!(f, "Tr(x + a + b / x^2) = 1, so no point has this x-coordinate"),
+            Self::NotOnCurve => write!(f, "decoded point doesn't lie on the curve"),
+            Self::Infinity => write!(f, "point at infinity has no encoding in this format"),
+        }
+    }
+}
+
+impl Error for EncodingError {}
+
+/// Big-endian octet string of ELEMENT_BYTES bytes, as in SEC1 2.3.5
+pub fn element_to_bytes(el: &GF2Element<DIM>) -> Vec<u8> {
+    (0..ELEMENT_BYTES).rev().map(|j| {
+        let bit = 8 * j;
+        (el.data[bit / WORD] >> (bit % WORD)) as u8
+    }).collect()
+}
+
+pub fn element_from_bytes(bytes: &[u8]) -> Result<GF2Element<DIM>, EncodingError> {
+    if bytes.len() != ELEMENT_BYTES {
+        return Err(EncodingError::InvalidLength);
+    }
+
+    let mut el = GF2Element::ZERO;
+    for (j, byte) in bytes.iter().rev().enumerate() {
+        let bit = 8 * j;
+        el.data[bit / WORD] |= (*byte as usize) << (bit % WORD);
+    }
+    if el.deg() >= MOD_DEG {
+        return Err(EncodingError::NotAnElement);
+    }
+    Ok(el)
+}
+
+impl Curve {
+    /// SEC1 2.3.3: 00 for the point at infinity, 02 / 03 || x with the low bit of y / x in the prefix,
+    /// or 04 || x || y
+    pub fn encode_point(&self, p: &Point, compressed: bool) -> Vec<u8> {
+        let (x, y) = match p {
+            Point::Infinity => return vec![0x00],
+            Point::Affine { x, y } => (x, y),
+        };
+
+        let mut out = Vec::with_capacity(1 + 2 * ELEMENT_BYTES);
+        if compressed {
+            out.push(0x02 | self.y_bit(x, y) as u8);
+            out.extend(element_to_bytes(x));
+        } else {
+            out.push(0x04);
+            out.extend(element_to_bytes(x));
+            out.extend(element_to_bytes(y));
+        }
+        out
+    }
+
+    /// SEC1 2.3.4, the y-coordinate of a compressed point is the root of z^2 + z = x + a + b / x^2
+    /// with the matching low bit, times x
+    pub fn decode_point(&self, bytes: &[u8]) -> Result<Point, EncodingError> {
+        let (prefix, rest) = bytes.split_first().ok_or(EncodingError::InvalidLength)?;
+        match (*prefix, rest.len()) {
+            (0x00, 0) => Ok(Point::Infinity),
+            (0x02 | 0x03, ELEMENT_BYTES) => {
+                let x = element_from_bytes(rest)?;
+                let bit = prefix & 1 == 1;
+                self.lift_x(x, |z| z.get_coef(0) == bit)
+            }
+            (0x04, len) if len == 2 * ELEMENT_BYTES => {
+                let x = element_from_bytes(&rest[..ELEMENT_BYTES])?;
+                let y = element_from_bytes(&rest[ELEMENT_BYTES..])?;
+                self.point(x, y).map_err(|_| EncodingError::NotOnCurve)
+            }
+            (0x00 | 0x02 | 0x03 | 0x04, _) => Err(EncodingError::InvalidLength),
+            _ => Err(EncodingError::InvalidPrefix),
+        }
+    }
+
+    /// DSTU 4145-2002 6.9: x with its lowest coefficient replaced by Tr(y / x), zero for x = 0.
+    /// Only points with Tr(x) = Tr(a), such as the points of the base point subgroup, decompress back.
+    pub fn dstu_compress(&self, p: &Point) -> Result<GF2Element<DIM>, EncodingError> {
+        let (x, y) = match p {
+            Point::Infinity => return Err(EncodingError::Infinity),
+            Point::Affine { x, y } => (x, y),
+        };
+        if x == &GF2Element::ZERO {
+            return Ok(GF2Element::ZERO);
+        }
+
+        let mut out = x.clone();
+        out.data[0] &= !1;
+        if y.mul(&x.inverse()).trace() == GF2Element::ONE {
+            out.data[0] |= 1;
+        }
+        Ok(out)
+    }
+
+    /// DSTU 4145-2002 6.10: the lowest coefficient of x is restored from Tr(x) = Tr(a),
+    /// then y = z x for the root z of z^2 + z = x + a + b / x^2 with the stored trace
+    pub fn dstu_decompress(&self, compressed: &GF2Element<DIM>) -> Result<Point, EncodingError> {
+        if compressed == &GF2Element::ZERO {
+            // y^2 = b
+            return Ok(Point::Affine { x: GF2Element::ZERO, y: self.b().frobenius(MOD_DEG - 1) });
+        }
+
+        let bit = compressed.get_coef(0);
+        let mut x = compressed.clone();
+        if x.trace() != self.a().trace() {
+            x.data[0] ^= 1;
+        }
+        self.lift_x(x, |z| z.trace().get_coef(0) == bit)
+    }
+
+    /// DSTU 4145 byte form: the compressed element, or x || y, both big-endian
+    pub fn dstu_encode_point(&self, p: &Point, compressed: bool) -> Result<Vec<u8>, EncodingError> {
+        if compressed {
+            return Ok(element_to_bytes(&self.dstu_compress(p)?));
+        }
+        match p {
+            Point::Infinity => Err(EncodingError::Infinity),
+            Point::Affine { x, y } => Ok([element_to_bytes(x), element_to_bytes(y)].concat()),
+        }
+    }
+
+    pub fn dstu_decode_point(&self, bytes: &[u8]) -> Result<Point, EncodingError> {
+        if bytes.len() == ELEMENT_BYTES {
+            return self.dstu_decompress(&element_from_bytes(bytes)?);
+        }
+        if bytes.len() != 2 * ELEMENT_BYTES {
+            return Err(EncodingError::InvalidLength);
+        }
+
+        let x = element_from_bytes(&bytes[..ELEMENT_BYTES])?;
+        let y = element_from_bytes(&bytes[ELEMENT_BYTES..])?;
+        self.point(x, y).map_err(|_| EncodingError::NotOnCurve)
+    }
+
+    // Low bit of y / x, zero for x = 0
+    fn y_bit(&self, x: &GF2Element<DIM>, y: &GF2Element<DIM>) -> bool {
+        x != &GF2Element::ZERO && y.mul(&x.inverse()).get_coef(0)
+    }
+
+    // Point with the given x, the root z of z^2 + z = x + a + b / x^2 is picked by `select`,
+    // the other root is z + 1
+    fn lift_x<S>(&self, x: GF2Element<DIM>, select: S) -> Result<Point, EncodingError>
+        where S: Fn(&GF2Element<DIM>) -> bool
+    {
+        if x == GF2Element::ZERO {
+            return Ok(Point::Affine { x, y: self.b().frobenius(MOD_DEG - 1) });
+        }
+
+        let beta = x.add(self.a()).add(&self.b().mul(&x.sqr().inverse()));
+        if beta.trace() != GF2Element::ZERO {
+            return Err(EncodingError::NoPointWithX);
+        }
+
+        let mut z = halftrace(&beta);
+        if !select(&z) {
+            z = z.add(&GF2Element::ONE);
+        }
+        let y = z.mul(&x);
+        Ok(Point::Affine { x, y })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::curve::tests::{point_after, test_curve};
+    use super::*;
+
+    #[test]
+    fn element_bytes_test() -> Result<(), Box<dyn Error>> {
+        let mut el = GF2Element::ZERO;
+        el.data[0] = 0x0102;
+        el.data[6] = 1 << (MOD_DEG - 1 - 6 * WORD);
+
+        let bytes = element_to_bytes(&el);
+        assert_eq!(bytes.len(), 53);
+        assert_eq!(&bytes[51..], &[0x01, 0x02]);
+        assert_eq!(bytes[0], 0x04);
+        assert_eq!(element_from_bytes(&bytes)?, el);
+
+        let mut too_big = bytes.clone();
+        too_big[0] = 0x08;
+        assert_eq!(element_from_bytes(&too_big), Err(EncodingError::NotAnElement));
+        assert_eq!(element_from_bytes(&bytes[1..]), Err(EncodingError::InvalidLength));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sec1_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let p = point_after(&curve, 2);
+        let minus_p = curve.neg(&p);
+
+        for q in [&p, &minus_p] {
+            let compressed = curve.encode_point(q, true);
+            assert_eq!(compressed.len(), 1 + ELEMENT_BYTES);
+            assert_eq!(&curve.decode_point(&compressed)?, q);
+            assert_eq!(&curve.decode_point(&curve.encode_point(q, false))?, q);
+        }
+        assert_ne!(curve.encode_point(&p, true), curve.encode_point(&minus_p, true));
+        assert_eq!(curve.decode_point(&curve.encode_point(&Point::Infinity, true))?, Point::Infinity);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dstu_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        // doubled points satisfy Tr(x) = Tr(a)
+        let p = curve.double(&point_after(&curve, 2));
+        let minus_p = curve.neg(&p);
+
+        for q in [&p, &minus_p] {
+            let compressed = curve.dstu_compress(q)?;
+            assert_eq!(&curve.dstu_decompress(&compressed)?, q);
+            assert_eq!(&curve.dstu_decode_point(&curve.dstu_encode_point(q, true)?)?, q);
+            assert_eq!(&curve.dstu_decode_point(&curve.dstu_encode_point(q, false)?)?, q);
+        }
+        assert_eq!(curve.dstu_compress(&Point::Infinity), Err(EncodingError::Infinity));
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+
+        // half of the x-coordinates belong to no point
+        let mut x = GF2Element::ZERO;
+        let mut found = false;
+        for k in 2..40 {
+            x.data[0] = k;
+            if curve.decode_point(&[vec![0x02], element_to_bytes(&x)].concat()) == Err(EncodingError::NoPointWithX) {
+                found = true;
+                break;
+            }
+        }
+        assert!(found);
+
+        assert_eq!(curve.decode_point(&[0x05; 1 + ELEMENT_BYTES]), Err(EncodingError::InvalidPrefix));
+        assert_eq!(curve.decode_point(&[0x04; 1 + ELEMENT_BYTES]), Err(EncodingError::InvalidLength));
+        assert_eq!(curve.decode_point(&[]), Err(EncodingError::InvalidLength));
+
+        let mut bad = curve.encode_point(&point_after(&curve, 2), false);
+        bad[2 * ELEMENT_BYTES] ^= 1;
+        assert_eq!(curve.decode_point(&bad), Err(EncodingError::NotOnCurve));
+
+        Ok(())
+    }
+}
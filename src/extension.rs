@@ -0,0 +1,470 @@
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::OnceLock;
+use vl_big_ints::UnsignedLongInt;
+use crate::field::BinaryField;
+use crate::{GF2Element, DIM};
+
+/// Parameters of an extension F[x] / (m(x)) of a binary field F
+pub trait ExtensionParams: Debug + Clone + Copy + PartialEq + Eq + Hash {
+    type Base: BinaryField + 'static;
+    const DEGREE: usize;
+
+    /// Lower coefficients c_0, ..., c_(k-1) of the monic irreducible modulus x^k + c_(k-1) x^(k-1) + ... + c_0
+    fn modulus() -> Vec<Self::Base>;
+
+    fn tables() -> &'static ExtensionTables<Self::Base>;
+}
+
+/// Modulus and the images x^(q i) of the Frobenius map a -> a^q, q = |F|
+#[derive(Debug)]
+pub struct ExtensionTables<F: BinaryField> {
+    modulus: Vec<F>,
+    frobenius: Vec<Vec<F>>,
+}
+
+impl<F: BinaryField> ExtensionTables<F> {
+    pub fn build<P: ExtensionParams<Base=F>>() -> Self {
+        let modulus = P::modulus();
+        assert_eq!(modulus.len(), P::DEGREE, "modulus must have DEGREE lower coefficients");
+        let k = P::DEGREE;
+
+        let mut x = vec![F::zero(); k];
+        x[1 % k] = F::one();
+        if k == 1 {
+            x[0] = modulus[0].clone();
+        }
+
+        // x^q by squaring deg(F) times
+        let mut x_q = x;
+        for _ in 0..F::DEGREE {
+            x_q = reduce(spread_sqr(&x_q), &modulus);
+        }
+
+        let mut frobenius = Vec::with_capacity(k);
+        let mut p = vec![F::zero(); k];
+        p[0] = F::one();
+        for _ in 0..k {
+            frobenius.push(p.clone());
+            p = reduce(poly_mul(&p, &x_q), &modulus);
+        }
+
+        Self { modulus, frobenius }
+    }
+}
+
+/// Element of the extension as a polynomial in x of degree below DEGREE
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extension<P: ExtensionParams> {
+    coefs: Vec<P::Base>,
+    params: PhantomData<P>,
+}
+
+impl<P: ExtensionParams> Eq for Extension<P> where P::Base: Eq {}
+
+impl<P: ExtensionParams> Hash for Extension<P> where P::Base: Hash {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.coefs.hash(state);
+    }
+}
+
+impl<P: ExtensionParams> Extension<P> {
+    /// Coefficients from the lowest degree
+    pub fn new(coefs: Vec<P::Base>) -> Self {
+        assert_eq!(coefs.len(), P::DEGREE, "wrong number of coefficients");
+        Self { coefs, params: PhantomData }
+    }
+
+    pub fn from_base(el: P::Base) -> Self {
+        let mut coefs = vec![P::Base::zero(); P::DEGREE];
+        coefs[0] = el;
+        Self::new(coefs)
+    }
+
+    pub fn coefs(&self) -> &[P::Base] {
+        &self.coefs
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        Self::new(self.coefs.iter().zip(other.coefs.iter()).map(|(a, b)| a.add(b)).collect())
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        Self::new(reduce(poly_mul(&self.coefs, &other.coefs), &P::tables().modulus))
+    }
+
+    pub fn sqr(&self) -> Self {
+        Self::new(reduce(spread_sqr(&self.coefs), &P::tables().modulus))
+    }
+
+    pub fn scale(&self, c: &P::Base) -> Self {
+        Self::new(self.coefs.iter().map(|a| a.mul(c)).collect())
+    }
+
+    pub fn pow(&self, e: &UnsignedLongInt) -> Self {
+        let mut result = Self::from_base(P::Base::one());
+        let Some(top) = e.get_highest_set_bit() else {
+            return result;
+        };
+
+        for i in (0..top + 1).rev() {
+            result = result.sqr();
+            if e.get_bit(i) {
+                result = result.mul(self);
+            }
+        }
+        result
+    }
+
+    /// a^(q^k) with q = |F|, coefficients stay in place since c^q = c for c in F
+    pub fn frobenius(&self, k: usize) -> Self {
+        let images = &P::tables().frobenius;
+        let mut out = self.clone();
+        for _ in 0..k % P::DEGREE {
+            let mut next = vec![P::Base::zero(); P::DEGREE];
+            for (a, image) in out.coefs.iter().zip(images.iter()) {
+                if a.is_zero() {
+                    continue;
+                }
+                for (n, x) in next.iter_mut().zip(image.iter()) {
+                    *n = n.add(&a.mul(x));
+                }
+            }
+            out = Self::new(next);
+        }
+        out
+    }
+
+    /// Product of all conjugates, lies in the base field
+    pub fn norm(&self) -> P::Base {
+        let mut out = self.clone();
+        let mut conjugate = self.clone();
+        for _ in 1..P::DEGREE {
+            conjugate = conjugate.frobenius(1);
+            out = out.mul(&conjugate);
+        }
+        out.coefs[0].clone()
+    }
+
+    /// Sum of all conjugates, lies in the base field
+    pub fn relative_trace(&self) -> P::Base {
+        let mut out = self.clone();
+        let mut conjugate = self.clone();
+        for _ in 1..P::DEGREE {
+            conjugate = conjugate.frobenius(1);
+            out = out.add(&conjugate);
+        }
+        out.coefs[0].clone()
+    }
+
+    /// a^-1 = (a^q ... a^(q^(k-1))) / N(a) with a single inversion in the base field, zero maps to zero
+    pub fn inverse(&self) -> Self {
+        let mut others = Self::from_base(P::Base::one());
+        let mut conjugate = self.clone();
+        for _ in 1..P::DEGREE {
+            conjugate = conjugate.frobenius(1);
+            others = others.mul(&conjugate);
+        }
+
+        let norm = self.mul(&others).coefs[0].clone();
+        others.scale(&norm.inverse())
+    }
+
+    /// Absolute trace to GF(2) through the base field
+    pub fn trace(&self) -> Self {
+        Self::from_base(self.relative_trace().trace())
+    }
+}
+
+/// GF(2^(4 * 419)) as GF(2^419)[x] / (x^4 + x + 1), irreducible since gcd(4, 419) = 1.
+/// GF(16) = GF(2)[x] / (x^4 + x + 1) is a subfield, which the pairing needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Quartic;
+
+pub type QuarticElement = Extension<Quartic>;
+
+impl ExtensionParams for Quartic {
+    type Base = GF2Element<DIM>;
+    const DEGREE: usize = 4;
+
+    fn modulus() -> Vec<GF2Element<DIM>> {
+        vec![GF2Element::ONE, GF2Element::ONE, GF2Element::ZERO, GF2Element::ZERO]
+    }
+
+    fn tables() -> &'static ExtensionTables<GF2Element<DIM>> {
+        static TABLES: OnceLock<ExtensionTables<GF2Element<DIM>>> = OnceLock::new();
+        TABLES.get_or_init(ExtensionTables::build::<Self>)
+    }
+}
+
+impl<P: ExtensionParams> BinaryField for Extension<P> {
+    const DEGREE: usize = P::DEGREE * P::Base::DEGREE;
+
+    fn zero() -> Self {
+        Self::new(vec![P::Base::zero(); P::DEGREE])
+    }
+
+    fn one() -> Self {
+        Self::from_base(P::Base::one())
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Extension::add(self, other)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        Extension::mul(self, other)
+    }
+
+    fn sqr(&self) -> Self {
+        Extension::sqr(self)
+    }
+
+    fn pow(&self, e: &UnsignedLongInt) -> Self {
+        Extension::pow(self, e)
+    }
+
+    fn inverse(&self) -> Self {
+        Extension::inverse(self)
+    }
+
+    fn trace(&self) -> Self {
+        Extension::trace(self)
+    }
+}
+
+// Product of polynomials of the same length k, Karatsuba formulas for k = 2, 3, 4
+fn poly_mul<F: BinaryField>(a: &[F], b: &[F]) -> Vec<F> {
+    match a.len() {
+        2 => karatsuba2(a, b),
+        3 => karatsuba3(a, b),
+        4 => karatsuba4(a, b),
+        _ => schoolbook(a, b),
+    }
+}
+
+fn schoolbook<F: BinaryField>(a: &[F], b: &[F]) -> Vec<F> {
+    let mut out = vec![F::zero(); a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            out[i + j] = out[i + j].add(&x.mul(y));
+        }
+    }
+    out
+}
+
+// 3 multiplications
+fn karatsuba2<F: BinaryField>(a: &[F], b: &[F]) -> Vec<F> {
+    let v0 = a[0].mul(&b[0]);
+    let v1 = a[1].mul(&b[1]);
+    let mid = a[0].add(&a[1]).mul(&b[0].add(&b[1])).add(&v0).add(&v1);
+    vec![v0, mid, v1]
+}
+
+// 6 multiplications
+fn karatsuba3<F: BinaryField>(a: &[F], b: &[F]) -> Vec<F> {
+    let v0 = a[0].mul(&b[0]);
+    let v1 = a[1].mul(&b[1]);
+    let v2 = a[2].mul(&b[2]);
+    let v01 = a[0].add(&a[1]).mul(&b[0].add(&b[1]));
+    let v02 = a[0].add(&a[2]).mul(&b[0].add(&b[2]));
+    let v12 = a[1].add(&a[2]).mul(&b[1].add(&b[2]));
+
+    vec![
+        v0.clone(),
+        v01.add(&v0).add(&v1),
+        v02.add(&v0).add(&v2).add(&v1),
+        v12.add(&v1).add(&v2),
+        v2,
+    ]
+}
+
+// 9 multiplications, Karatsuba over halves of length 2
+fn karatsuba4<F: BinaryField>(a: &[F], b: &[F]) -> Vec<F> {
+    let low = karatsuba2(&a[..2], &b[..2]);
+    let high = karatsuba2(&a[2..], &b[2..]);
+    let a_sum = [a[0].add(&a[2]), a[1].add(&a[3])];
+    let b_sum = [b[0].add(&b[2]), b[1].add(&b[3])];
+    let mid = karatsuba2(&a_sum, &b_sum);
+
+    let mut out = vec![F::zero(); 7];
+    for i in 0..3 {
+        out[i] = out[i].add(&low[i]);
+        out[i + 4] = out[i + 4].add(&high[i]);
+        out[i + 2] = out[i + 2].add(&mid[i]).add(&low[i]).add(&high[i]);
+    }
+    out
+}
+
+// In characteristic 2 squaring only squares the coefficients
+fn spread_sqr<F: BinaryField>(a: &[F]) -> Vec<F> {
+    let mut out = vec![F::zero(); 2 * a.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        out[2 * i] = x.sqr();
+    }
+    out
+}
+
+fn reduce<F: BinaryField>(mut p: Vec<F>, modulus: &[F]) -> Vec<F> {
+    let k = modulus.len();
+    let one = F::one();
+    for i in (k..p.len()).rev() {
+        let c = p[i].clone();
+        if c.is_zero() {
+            continue;
+        }
+        // x^k = c_(k-1) x^(k-1) + ... + c_0
+        for (j, m) in modulus.iter().enumerate() {
+            if m.is_zero() {
+                continue;
+            }
+            let term = if m == &one { c.clone() } else { c.mul(m) };
+            p[i - k + j] = p[i - k + j].add(&term);
+        }
+    }
+    p.truncate(k);
+    p
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::small_field::GF256;
+    use super::*;
+
+    // GF(2^16) as GF(2^8)[x] / (x^2 + x + 0x20), Tr(0x20) = 1
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Quadratic;
+
+    impl ExtensionParams for Quadratic {
+        type Base = GF256;
+        const DEGREE: usize = 2;
+
+        fn modulus() -> Vec<GF256> {
+            vec![GF256::new(0x20), GF256::ONE]
+        }
+
+        fn tables() -> &'static ExtensionTables<GF256> {
+            static TABLES: OnceLock<ExtensionTables<GF256>> = OnceLock::new();
+            TABLES.get_or_init(ExtensionTables::build::<Self>)
+        }
+    }
+
+    // GF(2^24) as GF(2^8)[x] / (x^3 + x + 1)
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Cubic;
+
+    impl ExtensionParams for Cubic {
+        type Base = GF256;
+        const DEGREE: usize = 3;
+
+        fn modulus() -> Vec<GF256> {
+            vec![GF256::ONE, GF256::ONE, GF256::ZERO]
+        }
+
+        fn tables() -> &'static ExtensionTables<GF256> {
+            static TABLES: OnceLock<ExtensionTables<GF256>> = OnceLock::new();
+            TABLES.get_or_init(ExtensionTables::build::<Self>)
+        }
+    }
+
+    // GF(2^32) as GF(2^16)[y] / (y^2 + y + 0x20 x), a tower on top of Quadratic
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Tower;
+
+    impl ExtensionParams for Tower {
+        type Base = Extension<Quadratic>;
+        const DEGREE: usize = 2;
+
+        fn modulus() -> Vec<Extension<Quadratic>> {
+            vec![Extension::new(vec![GF256::ZERO, GF256::new(0x20)]), Extension::one()]
+        }
+
+        fn tables() -> &'static ExtensionTables<Extension<Quadratic>> {
+            static TABLES: OnceLock<ExtensionTables<Extension<Quadratic>>> = OnceLock::new();
+            TABLES.get_or_init(ExtensionTables::build::<Self>)
+        }
+    }
+
+    fn random_gf256(k: usize) -> Vec<GF256> {
+        (0..k).map(|_| GF256::new(rand::random::<u8>() as u16)).collect()
+    }
+
+    #[test]
+    fn karatsuba_test() {
+        for k in 2..5 {
+            let a = random_gf256(k);
+            let b = random_gf256(k);
+            assert_eq!(poly_mul(&a, &b), schoolbook(&a, &b));
+        }
+    }
+
+    #[test]
+    fn quadratic_test() {
+        for _ in 0..100 {
+            let a = Extension::<Quadratic>::new(random_gf256(2));
+            let b = Extension::<Quadratic>::new(random_gf256(2));
+            if a.is_zero() {
+                continue;
+            }
+
+            assert_eq!(a.mul(&a.inverse()), Extension::one());
+            assert_eq!(a.mul(&b).norm(), a.norm().mul(&b.norm()));
+            assert_eq!(a.frobenius(1), a.pow(&UnsignedLongInt::from(256)));
+            assert_eq!(a.frobenius(2), a);
+            assert_eq!(a.sqr(), a.mul(&a));
+        }
+    }
+
+    #[test]
+    fn cubic_test() {
+        let mut ones = 0;
+        for _ in 0..100 {
+            let a = Extension::<Cubic>::new(random_gf256(3));
+            let b = Extension::<Cubic>::new(random_gf256(3));
+            let c = Extension::<Cubic>::new(random_gf256(3));
+            if a.is_zero() {
+                continue;
+            }
+
+            assert_eq!(a.mul(&a.inverse()), Extension::one());
+            assert_eq!(a.add(&b).mul(&c), a.mul(&c).add(&b.mul(&c)));
+            assert_eq!(a.frobenius(3), a);
+            assert_eq!(a.add(&b).relative_trace(), a.relative_trace().add(&b.relative_trace()));
+            if a.trace() == Extension::one() {
+                ones += 1;
+            }
+        }
+        assert!(ones > 0);
+    }
+
+    #[test]
+    fn tower_test() {
+        assert_eq!(<Extension<Tower> as BinaryField>::DEGREE, 32);
+        for _ in 0..20 {
+            let a = Extension::<Tower>::new(vec![
+                Extension::new(random_gf256(2)),
+                Extension::new(random_gf256(2)),
+            ]);
+            if a.is_zero() {
+                continue;
+            }
+            assert_eq!(a.mul(&a.inverse()), Extension::one());
+            assert_eq!(a.pow(&UnsignedLongInt::from(u32::MAX as u64)), Extension::one());
+        }
+    }
+
+    #[test]
+    fn quartic_test() {
+        let a = Extension::<Quartic>::new(vec![
+            GF2Element::from([3; 2 * DIM]),
+            GF2Element::ZERO,
+            GF2Element::ONE,
+            GF2Element::from([7; 2 * DIM]),
+        ]);
+
+        assert_eq!(a.mul(&a.inverse()), Extension::one());
+        assert_eq!(a.frobenius(4), a);
+        assert_eq!(a.frobenius(1).frobenius(3), a);
+    }
+}
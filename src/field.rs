@@ -0,0 +1,150 @@
+use std::fmt::Debug;
+use rand::RngCore;
+use vl_big_ints::UnsignedLongInt;
+use crate::{GF2Element, DIM, MOD_DEG};
+use crate::validation::modulus_exponents;
+
+/// Common interface of the binary (characteristic 2) fields, so subtraction is the same as addition
+pub trait BinaryField: Sized + Clone + PartialEq + Debug {
+    /// Degree of the field over GF(2)
+    const DEGREE: usize;
+
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn add(&self, other: &Self) -> Self;
+    fn mul(&self, other: &Self) -> Self;
+    fn sqr(&self) -> Self;
+    fn pow(&self, e: &UnsignedLongInt) -> Self;
+    fn inverse(&self) -> Self;
+    fn trace(&self) -> Self;
+
+    fn is_zero(&self) -> bool {
+        self == &Self::zero()
+    }
+}
+
+impl BinaryField for GF2Element<DIM> {
+    const DEGREE: usize = MOD_DEG;
+
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        GF2Element::add(self, other)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        GF2Element::mul(self, other)
+    }
+
+    fn sqr(&self) -> Self {
+        GF2Element::sqr(self)
+    }
+
+    fn pow(&self, e: &UnsignedLongInt) -> Self {
+        GF2Element::pow(self, e)
+    }
+
+    fn inverse(&self) -> Self {
+        GF2Element::inverse(self)
+    }
+
+    fn trace(&self) -> Self {
+        GF2Element::trace(self)
+    }
+}
+
+/// Field given in the polynomial basis of GF(2)[x] / f(x), elements convert to and from
+/// little-endian 64-bit words with bit i the coefficient at x^i
+pub trait PolynomialBasis: BinaryField {
+    /// Exponents of f, the highest first
+    fn modulus() -> Vec<usize>;
+    fn to_words(&self) -> Vec<u64>;
+    /// None when a coefficient at x^m or above is set
+    fn from_words(words: &[u64]) -> Option<Self>;
+}
+
+impl PolynomialBasis for GF2Element<DIM> {
+    fn modulus() -> Vec<usize> {
+        modulus_exponents()
+    }
+
+    fn to_words(&self) -> Vec<u64> {
+        self.data.iter().map(|w| *w as u64).collect()
+    }
+
+    fn from_words(words: &[u64]) -> Option<Self> {
+        let mut el = GF2Element::ZERO;
+        for (i, w) in words.iter().enumerate().filter(|(_, w)| **w != 0) {
+            *el.data.get_mut(i)? = *w as usize;
+        }
+        (el.deg() < MOD_DEG).then_some(el)
+    }
+}
+
+/// Field elements that can be drawn from a random generator
+pub trait RandomElement: BinaryField {
+    fn random<R: RngCore>(rng: &mut R) -> Self;
+}
+
+impl RandomElement for GF2Element<DIM> {
+    fn random<R: RngCore>(rng: &mut R) -> Self {
+        let mut words = [0usize; 2 * DIM];
+        words[..DIM].iter_mut().for_each(|w| *w = rng.next_u64() as usize);
+        // the reduction is linear and onto, so every element is hit equally often
+        GF2Element::from(words)
+    }
+}
+
+/// Inverts all elements with a single field inversion (Montgomery's trick), elements must be nonzero
+pub fn batch_inverse<F: BinaryField>(elements: &[F]) -> Vec<F> {
+    if elements.is_empty() {
+        return Vec::new();
+    }
+
+    // prefix[i] = elements[0] * ... * elements[i]
+    let mut prefix = Vec::with_capacity(elements.len());
+    let mut acc = F::one();
+    for e in elements {
+        acc = acc.mul(e);
+        prefix.push(acc.clone());
+    }
+
+    let mut inv = acc.inverse();
+    let mut out = vec![F::zero(); elements.len()];
+    for i in (1..elements.len()).rev() {
+        out[i] = inv.mul(&prefix[i - 1]);
+        inv = inv.mul(&elements[i]);
+    }
+    out[0] = inv;
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::str::FromStr;
+    use super::*;
+
+    #[test]
+    fn batch_inverse_test() -> Result<(), Box<dyn Error>> {
+        let elements: Vec<GF2Element<DIM>> = vec![
+            GF2Element::from_str("050E04B10B1CA453CDA09E9F9EAF055A3F6EE35A162EC9E390CC67888B2FDE0EA1DD5B2A2C9E6E373F3DE18B5621FF810F0C338D71")?,
+            GF2Element::from_str("066A1CDA81DFBD5953500236E1D5264911779ECCBCBF1241AC2886FF71AB374B7DD0A28E6863801FF40507229FE65223587491D2CD")?,
+            GF2Element::from_str("04C1190B05D7B06470D4D030368B91BF48FBC8D207BF309F7CB87C21451DABCD293D5A560A437808BDF4184C96951A1B3F698FBD70")?,
+        ];
+
+        let inverses = batch_inverse(&elements);
+        for (e, i) in elements.iter().zip(inverses.iter()) {
+            assert_eq!(e.mul(i), GF2Element::ONE);
+        }
+
+        Ok(())
+    }
+}
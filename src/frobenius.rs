@@ -0,0 +1,148 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::{GF2Element, DIM, MOD_DEG};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SubfieldError {
+    NotADivisor,
+}
+
+impl Display for SubfieldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotADivisor => write!(f, "subfield degree doesn't divide the field degree"),
+        }
+    }
+}
+
+impl Error for SubfieldError {}
+
+impl GF2Element<DIM> {
+    /// a^(2^k) by repeated squaring, k is taken modulo m.
+    /// `BitMatrix::frobenius(k)` is cheaper when the same power is applied to many elements.
+    pub fn frobenius(&self, k: usize) -> Self {
+        let mut out = self.clone();
+        for _ in 0..k % MOD_DEG {
+            out = out.sqr();
+        }
+        out
+    }
+
+    /// Frobenius orbit a, a^2, a^4, ... up to the first repetition
+    pub fn conjugates(&self) -> Conjugates {
+        Conjugates { first: self.clone(), next: Some(self.clone()) }
+    }
+
+    /// N(a) = a^(2^m - 1) as a GF(2) value, which is 1 for every non-zero element
+    pub fn norm(&self) -> bool {
+        self != &GF2Element::ZERO
+    }
+
+    /// Trace down to the subfield GF(2^d), sum of a^(2^(d i)) for i < m / d
+    pub fn relative_trace(&self, d: usize) -> Result<Self, SubfieldError> {
+        let steps = subfield_steps(d)?;
+
+        let mut conjugate = self.clone();
+        let mut out = self.clone();
+        for _ in 1..steps {
+            conjugate = conjugate.frobenius(d);
+            out = out.add(&conjugate);
+        }
+        Ok(out)
+    }
+
+    /// Norm down to the subfield GF(2^d), product of a^(2^(d i)) for i < m / d
+    pub fn relative_norm(&self, d: usize) -> Result<Self, SubfieldError> {
+        let steps = subfield_steps(d)?;
+
+        let mut conjugate = self.clone();
+        let mut out = self.clone();
+        for _ in 1..steps {
+            conjugate = conjugate.frobenius(d);
+            out = out.mul(&conjugate);
+        }
+        Ok(out)
+    }
+}
+
+/// Iterator over the distinct conjugates of an element
+#[derive(Debug, Clone)]
+pub struct Conjugates {
+    first: GF2Element<DIM>,
+    next: Option<GF2Element<DIM>>,
+}
+
+impl Iterator for Conjugates {
+    type Item = GF2Element<DIM>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+        let following = current.sqr();
+        if following != self.first {
+            self.next = Some(following);
+        }
+        Some(current)
+    }
+}
+
+// m / d for a subfield GF(2^d)
+fn subfield_steps(d: usize) -> Result<usize, SubfieldError> {
+    if d == 0 || !MOD_DEG.is_multiple_of(d) {
+        return Err(SubfieldError::NotADivisor);
+    }
+    Ok(MOD_DEG / d)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::str::FromStr;
+    use super::*;
+
+    #[test]
+    fn frobenius_test() -> Result<(), Box<dyn Error>> {
+        let a = GF2Element::from_str("050E04B10B1CA453CDA09E9F9EAF055A3F6EE35A162EC9E390CC67888B2FDE0EA1DD5B2A2C9E6E373F3DE18B5621FF810F0C338D71")?;
+
+        assert_eq!(a.frobenius(0), a);
+        assert_eq!(a.frobenius(1), a.mul(&a));
+        assert_eq!(a.frobenius(3), a.sqr().sqr().sqr());
+        assert_eq!(a.frobenius(MOD_DEG), a);
+        assert_eq!(a.frobenius(MOD_DEG - 1).sqr(), a);
+        assert_eq!(a.frobenius(100).frobenius(MOD_DEG - 100), a);
+
+        Ok(())
+    }
+
+    #[test]
+    fn conjugates_test() -> Result<(), Box<dyn Error>> {
+        let a = GF2Element::from_str("066A1CDA81DFBD5953500236E1D5264911779ECCBCBF1241AC2886FF71AB374B7DD0A28E6863801FF40507229FE65223587491D2CD")?;
+
+        let conjugates: Vec<_> = a.conjugates().collect();
+        assert_eq!(conjugates.len(), MOD_DEG);
+        assert_eq!(conjugates[5], a.frobenius(5));
+        assert_eq!(GF2Element::ONE.conjugates().count(), 1);
+        assert_eq!(GF2Element::ZERO.conjugates().count(), 1);
+
+        // the sum of the orbit is the trace
+        let sum = conjugates.iter().fold(GF2Element::ZERO, |acc, c| acc.add(c));
+        assert_eq!(sum, a.trace());
+
+        Ok(())
+    }
+
+    #[test]
+    fn norm_test() -> Result<(), Box<dyn Error>> {
+        let a = GF2Element::from_str("04C1190B05D7B06470D4D030368B91BF48FBC8D207BF309F7CB87C21451DABCD293D5A560A437808BDF4184C96951A1B3F698FBD70")?;
+
+        assert!(a.norm());
+        assert!(!GF2Element::ZERO.norm());
+        assert_eq!(a.relative_norm(1)?, GF2Element::ONE);
+        assert_eq!(a.relative_trace(1)?, a.trace());
+        assert_eq!(a.relative_trace(MOD_DEG)?, a);
+        assert_eq!(a.relative_norm(MOD_DEG)?, a);
+        assert_eq!(a.relative_trace(2), Err(SubfieldError::NotADivisor));
+        assert_eq!(a.relative_norm(0), Err(SubfieldError::NotADivisor));
+
+        Ok(())
+    }
+}
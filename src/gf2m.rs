@@ -0,0 +1,405 @@
+use std::fmt::{Debug, Display, Formatter, LowerHex};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use vl_big_ints::UnsignedLongInt;
+use crate::field::{BinaryField, PolynomialBasis};
+use crate::from_str::GF2ElementFromStrError;
+
+/// Words of the largest supported field GF(2^571)
+pub const MAX_WORDS: usize = 9;
+
+const WORD: usize = u64::BITS as usize;
+
+/// Irreducible trinomial or pentanomial f(x) of a binary field in the polynomial basis
+pub trait Gf2mParams: Debug + Clone + Copy + PartialEq + Eq + Hash {
+    /// Exponents of f, the highest first
+    const MODULUS: &'static [usize];
+    const DEGREE: usize = Self::MODULUS[0];
+    const WORDS: usize = Self::DEGREE.div_ceil(WORD);
+}
+
+macro_rules! field_params {
+    ($($(#[$doc:meta])* $name:ident: $modulus:expr;)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub struct $name;
+
+            impl Gf2mParams for $name {
+                const MODULUS: &'static [usize] = &$modulus;
+            }
+        )*
+    };
+}
+
+field_params! {
+    /// x^113 + x^9 + 1 of SEC 2
+    Sect113: [113, 9, 0];
+    /// x^131 + x^8 + x^3 + x^2 + 1 of SEC 2
+    Sect131: [131, 8, 3, 2, 0];
+    /// x^163 + x^7 + x^6 + x^3 + 1 of SEC 2 and FIPS 186-4, DSTU 4145-2002 uses it as well
+    Sect163: [163, 7, 6, 3, 0];
+    /// x^193 + x^15 + 1 of SEC 2
+    Sect193: [193, 15, 0];
+    /// x^233 + x^74 + 1 of SEC 2 and FIPS 186-4
+    Sect233: [233, 74, 0];
+    /// x^239 + x^158 + 1 of SEC 2
+    Sect239: [239, 158, 0];
+    /// x^283 + x^12 + x^7 + x^5 + 1 of SEC 2 and FIPS 186-4
+    Sect283: [283, 12, 7, 5, 0];
+    /// x^409 + x^87 + 1 of SEC 2 and FIPS 186-4
+    Sect409: [409, 87, 0];
+    /// x^571 + x^10 + x^5 + x^2 + 1 of SEC 2 and FIPS 186-4
+    Sect571: [571, 10, 5, 2, 0];
+    /// x^167 + x^6 + 1 of DSTU 4145-2002
+    Dstu167: [167, 6, 0];
+    /// x^173 + x^10 + x^2 + x + 1 of DSTU 4145-2002
+    Dstu173: [173, 10, 2, 1, 0];
+    /// x^179 + x^4 + x^2 + x + 1 of DSTU 4145-2002
+    Dstu179: [179, 4, 2, 1, 0];
+    /// x^191 + x^9 + 1 of DSTU 4145-2002
+    Dstu191: [191, 9, 0];
+    /// x^233 + x^9 + x^4 + x + 1 of DSTU 4145-2002
+    Dstu233: [233, 9, 4, 1, 0];
+    /// x^257 + x^12 + 1 of DSTU 4145-2002
+    Dstu257: [257, 12, 0];
+    /// x^307 + x^8 + x^4 + x^2 + 1 of DSTU 4145-2002
+    Dstu307: [307, 8, 4, 2, 0];
+    /// x^367 + x^21 + 1 of DSTU 4145-2002
+    Dstu367: [367, 21, 0];
+    /// x^431 + x^5 + x^3 + x + 1 of DSTU 4145-2002
+    Dstu431: [431, 5, 3, 1, 0];
+}
+
+pub type GF2_163 = Gf2m<Sect163>;
+
+/// Element of GF(2^m) for a standard modulus, bit i of the little-endian words is the coefficient at x^i
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gf2m<P: Gf2mParams> {
+    data: [u64; MAX_WORDS],
+    params: PhantomData<P>,
+}
+
+impl<P: Gf2mParams> Gf2m<P> {
+    pub const ZERO: Self = Self { data: [0; MAX_WORDS], params: PhantomData };
+    pub const ONE: Self = {
+        let mut data = [0; MAX_WORDS];
+        data[0] = 1;
+        Self { data, params: PhantomData }
+    };
+
+    /// Element from little-endian words, None when a coefficient at x^m or above is set
+    pub fn from_words(words: &[u64]) -> Option<Self> {
+        let mut data = [0; MAX_WORDS];
+        for (i, w) in words.iter().enumerate().filter(|(_, w)| **w != 0) {
+            *data.get_mut(i)? = *w;
+        }
+        let el = Self { data, params: PhantomData };
+        (deg(&el.data).is_none_or(|d| d < P::DEGREE)).then_some(el)
+    }
+
+    pub fn words(&self) -> &[u64] {
+        &self.data[..P::WORDS]
+    }
+
+    pub fn get_coef(&self, i: usize) -> bool {
+        i < P::DEGREE && self.data[i / WORD] >> (i % WORD) & 1 == 1
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let mut data = self.data;
+        for (x, y) in data.iter_mut().zip(other.data.iter()) {
+            *x ^= y;
+        }
+        Self { data, params: PhantomData }
+    }
+
+    /// Right-to-left comb: the bits k of all the words of `other` share one shifted copy of `self`
+    pub fn mul(&self, other: &Self) -> Self {
+        let n = P::WORDS;
+        let mut product = [0u64; 2 * MAX_WORDS];
+        let mut shifted = [0u64; MAX_WORDS + 1];
+        shifted[..n].copy_from_slice(&self.data[..n]);
+        for k in 0..WORD {
+            for j in (0..n).filter(|&j| other.data[j] >> k & 1 == 1) {
+                for (p, s) in product[j..j + n + 1].iter_mut().zip(shifted.iter()) {
+                    *p ^= s;
+                }
+            }
+            // shifted = self * x^(k + 1)
+            for i in (0..n + 1).rev() {
+                shifted[i] = shifted[i] << 1 | if i > 0 { shifted[i - 1] >> (WORD - 1) } else { 0 };
+            }
+        }
+        reduce(product)
+    }
+
+    /// Squaring is linear, bit i moves to bit 2i
+    pub fn sqr(&self) -> Self {
+        let mut spread = [0u64; 2 * MAX_WORDS];
+        for i in (0..P::DEGREE).filter(|&i| self.get_coef(i)) {
+            spread[2 * i / WORD] |= 1 << (2 * i % WORD);
+        }
+        reduce(spread)
+    }
+
+    pub fn pow(&self, e: &UnsignedLongInt) -> Self {
+        let Some(top) = e.get_highest_set_bit() else {
+            return Self::ONE;
+        };
+        (0..top + 1).rev().fold(Self::ONE, |acc, i| {
+            let acc = acc.sqr();
+            if e.get_bit(i) { acc.mul(self) } else { acc }
+        })
+    }
+
+    /// Binary extended Euclid on (a, f), zero is mapped to zero like a^(q-2) would do
+    pub fn inverse(&self) -> Self {
+        if deg(&self.data).is_none() {
+            return Self::ZERO;
+        }
+
+        let mut u = [0u64; MAX_WORDS + 1];
+        u[..MAX_WORDS].copy_from_slice(&self.data);
+        let mut v = [0u64; MAX_WORDS + 1];
+        for &e in P::MODULUS {
+            v[e / WORD] |= 1 << (e % WORD);
+        }
+        // g1 a = u and g2 a = v mod f
+        let mut g1 = [0u64; MAX_WORDS + 1];
+        g1[0] = 1;
+        let mut g2 = [0u64; MAX_WORDS + 1];
+
+        while deg(&u) != Some(0) {
+            let (du, dv) = (deg(&u).expect("u stays non-zero"), deg(&v).expect("v stays non-zero"));
+            if du < dv {
+                std::mem::swap(&mut u, &mut v);
+                std::mem::swap(&mut g1, &mut g2);
+            }
+            let j = du.abs_diff(dv);
+            xor_shifted(&mut u, &v, j);
+            xor_shifted(&mut g1, &g2, j);
+        }
+
+        let mut data = [0; MAX_WORDS];
+        data.copy_from_slice(&g1[..MAX_WORDS]);
+        Self { data, params: PhantomData }
+    }
+
+    pub fn trace(&self) -> Self {
+        let mut s = *self;
+        let mut out = *self;
+        for _ in 1..P::DEGREE {
+            s = s.sqr();
+            out = out.add(&s);
+        }
+        out
+    }
+}
+
+impl<P: Gf2mParams> BinaryField for Gf2m<P> {
+    const DEGREE: usize = P::DEGREE;
+
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Gf2m::add(self, other)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        Gf2m::mul(self, other)
+    }
+
+    fn sqr(&self) -> Self {
+        Gf2m::sqr(self)
+    }
+
+    fn pow(&self, e: &UnsignedLongInt) -> Self {
+        Gf2m::pow(self, e)
+    }
+
+    fn inverse(&self) -> Self {
+        Gf2m::inverse(self)
+    }
+
+    fn trace(&self) -> Self {
+        Gf2m::trace(self)
+    }
+}
+
+impl<P: Gf2mParams> PolynomialBasis for Gf2m<P> {
+    fn modulus() -> Vec<usize> {
+        P::MODULUS.to_vec()
+    }
+
+    fn to_words(&self) -> Vec<u64> {
+        self.words().to_vec()
+    }
+
+    fn from_words(words: &[u64]) -> Option<Self> {
+        Gf2m::from_words(words)
+    }
+}
+
+impl<P: Gf2mParams> FromStr for Gf2m<P> {
+    type Err = GF2ElementFromStrError;
+    // for big-endian hex strings
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.is_ascii() {
+            return Err(GF2ElementFromStrError::ConversionError);
+        }
+
+        let mut words = Vec::with_capacity(s.len().div_ceil(16));
+        let mut end = s.len();
+        while end > 0 {
+            let start = end.saturating_sub(16);
+            words.push(u64::from_str_radix(&s[start..end], 16)?);
+            end = start;
+        }
+        Self::from_words(&words).ok_or(GF2ElementFromStrError::NotAnElementError)
+    }
+}
+
+impl<P: Gf2mParams> Display for Gf2m<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for pow in (1..P::DEGREE).rev().filter(|&i| self.get_coef(i)) {
+            write!(f, "x^{pow} + ")?;
+        }
+        write!(f, "{}]", self.data[0] & 1)
+    }
+}
+
+impl<P: Gf2mParams> LowerHex for Gf2m<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = P::DEGREE.div_ceil(4);
+        let top = digits - 16 * (P::WORDS - 1);
+        write!(f, "{:0top$x}", self.data[P::WORDS - 1])?;
+        for w in self.data[..P::WORDS - 1].iter().rev() {
+            write!(f, "{w:016x}")?;
+        }
+        Ok(())
+    }
+}
+
+// Clears the coefficients at x^i, i >= m, with x^m = f(x) - x^m from the top down
+fn reduce<P: Gf2mParams>(mut t: [u64; 2 * MAX_WORDS]) -> Gf2m<P> {
+    let m = P::DEGREE;
+    for i in (m..2 * m - 1).rev() {
+        if t[i / WORD] >> (i % WORD) & 1 == 0 {
+            continue;
+        }
+        for &e in P::MODULUS {
+            t[(i - m + e) / WORD] ^= 1 << ((i - m + e) % WORD);
+        }
+    }
+
+    let mut data = [0; MAX_WORDS];
+    data.copy_from_slice(&t[..MAX_WORDS]);
+    Gf2m { data, params: PhantomData }
+}
+
+fn deg(a: &[u64]) -> Option<usize> {
+    a.iter().enumerate().rev()
+        .find(|(_, w)| **w != 0)
+        .map(|(i, w)| i * WORD + (WORD - 1 - w.leading_zeros() as usize))
+}
+
+// a += b x^shift, the part shifted past the end of a has to be zero
+fn xor_shifted(a: &mut [u64], b: &[u64], shift: usize) {
+    let (words, bits) = (shift / WORD, shift % WORD);
+    for i in (0..b.len()).filter(|&i| b[i] != 0) {
+        a[i + words] ^= b[i] << bits;
+        if bits > 0 && b[i] >> (WORD - bits) != 0 {
+            a[i + words + 1] ^= b[i] >> (WORD - bits);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::validation::is_irreducible;
+    use super::*;
+
+    // Shift-and-add with the modulus as an integer, the reference for the comb
+    fn mul_slow<P: Gf2mParams>(a: &Gf2m<P>, b: &Gf2m<P>) -> Gf2m<P> {
+        let mut out = Gf2m::ZERO;
+        let mut a = *a;
+        let x = Gf2m::from_words(&[2]).unwrap();
+        for i in 0..P::DEGREE {
+            if b.get_coef(i) {
+                out = out.add(&a);
+            }
+            // a * x, with the reduction done by the top coefficient
+            let top = a.get_coef(P::DEGREE - 1);
+            let mut data = [0u64; 2 * MAX_WORDS];
+            for j in (0..P::DEGREE - 1).filter(|&j| a.get_coef(j)) {
+                data[(j + 1) / WORD] |= 1 << ((j + 1) % WORD);
+            }
+            a = reduce(data);
+            if top {
+                a = a.add(&x.pow(&UnsignedLongInt::from(P::DEGREE as u64)));
+            }
+        }
+        out
+    }
+
+    fn sample<P: Gf2mParams>(seed: u64) -> Gf2m<P> {
+        let words: Vec<u64> = (0..P::WORDS as u64).map(|i| (seed + i).wrapping_mul(0x9e37_79b9_7f4a_7c15)).collect();
+        let mut data = [0; MAX_WORDS];
+        data[..P::WORDS].copy_from_slice(&words);
+        let top = P::DEGREE % WORD;
+        if top > 0 {
+            data[P::WORDS - 1] &= (1 << top) - 1;
+        }
+        Gf2m { data, params: PhantomData }
+    }
+
+    fn check_field<P: Gf2mParams>() {
+        assert!(is_irreducible(P::MODULUS));
+        let (a, b) = (sample::<P>(1), sample::<P>(2));
+
+        assert_eq!(a.mul(&b), mul_slow(&a, &b));
+        assert_eq!(a.sqr(), a.mul(&a));
+        assert_eq!(a.mul(&a.inverse()), Gf2m::ONE);
+        assert!(a.trace() == Gf2m::ZERO || a.trace() == Gf2m::ONE);
+        // a^(2^m) = a
+        let q = UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(P::DEGREE as u64));
+        assert_eq!(a.pow(&q), a);
+    }
+
+    #[test]
+    fn arithmetic_test() {
+        check_field::<Sect113>();
+        check_field::<Sect163>();
+        check_field::<Sect239>();
+        check_field::<Sect571>();
+        check_field::<Dstu173>();
+        check_field::<Dstu431>();
+    }
+
+    #[test]
+    fn conversion_test() -> Result<(), Box<dyn Error>> {
+        // x^162 + 1 needs the third word
+        let el = GF2_163::from_str("40000000000000000000000000000000000000001")?;
+        assert_eq!(el.words(), &[1, 0, 1 << 34]);
+        assert_eq!(format!("{el:x}"), "40000000000000000000000000000000000000001");
+        assert_eq!(GF2_163::from_str("1")?, GF2_163::ONE);
+        assert!(el.get_coef(162) && !el.get_coef(163));
+
+        assert_eq!(GF2_163::from_str("80000000000000000000000000000000000000000"), Err(GF2ElementFromStrError::NotAnElementError));
+        assert_eq!(GF2_163::from_str("xyz"), Err(GF2ElementFromStrError::ConversionError));
+        assert_eq!(Gf2m::<Sect113>::from_words(&[1, 1 << 49]), None);
+        assert_eq!(format!("{}", Gf2m::<Sect113>::from_words(&[0b1011]).unwrap()), "[x^3 + x^1 + 1]");
+
+        Ok(())
+    }
+}
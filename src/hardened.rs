@@ -0,0 +1,222 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use rand::RngCore;
+use vl_big_ints::UnsignedLongInt;
+use crate::curve::Point;
+use crate::domain::DomainParameters;
+use crate::field::RandomElement;
+use crate::long_int::from_words;
+use crate::{GF2Element, DIM};
+
+/// Bits of the random multiple of n added to a blinded scalar
+pub const BLINDING_BITS: usize = 64;
+
+/// Curve layer countermeasures of `hardened_mul`, each one can be switched off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Countermeasures {
+    /// k + r n for a random r, so the ladder never runs on the secret scalar itself.
+    /// This needs nP = O, which point validation guarantees.
+    pub scalar_blinding: bool,
+    /// Ladder started from (x z : z) with a random z != 0
+    pub randomized_coordinates: bool,
+    /// Rejects input points off the curve, on the quadratic twist or outside the subgroup of order n
+    pub point_validation: bool,
+}
+
+impl Countermeasures {
+    pub const ALL: Self = Self { scalar_blinding: true, randomized_coordinates: true, point_validation: true };
+    pub const NONE: Self = Self { scalar_blinding: false, randomized_coordinates: false, point_validation: false };
+}
+
+impl Default for Countermeasures {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PointValidationError {
+    Infinity,
+    NotOnCurve,
+    OnTwist,
+    WrongSubgroup,
+}
+
+impl Display for PointValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Infinity => write!(f, "point is the point at infinity"),
+            Self::NotOnCurve => write!(f, "point doesn't lie on the curve"),
+            Self::OnTwist => write!(f, "x-coordinate belongs to a point of the quadratic twist"),
+            Self::WrongSubgroup => write!(f, "point is not in the subgroup of order n"),
+        }
+    }
+}
+
+impl Error for PointValidationError {}
+
+impl DomainParameters {
+    /// Defence against invalid-curve and small subgroup attacks: Q != O lies on the curve and nQ = O
+    pub fn validate_point(&self, q: &Point) -> Result<(), PointValidationError> {
+        if q.is_infinity() {
+            return Err(PointValidationError::Infinity);
+        }
+        if !self.curve.is_on_curve(q) {
+            return Err(PointValidationError::NotOnCurve);
+        }
+        if !self.curve.ld_mul(q, &self.order).is_infinity() {
+            return Err(PointValidationError::WrongSubgroup);
+        }
+        Ok(())
+    }
+
+    /// Defence against twist attacks on the x-only ladder: some point of E has this x,
+    /// that is Tr(x + a + b / x^2) = 0, and that point is killed by n
+    pub fn validate_x(&self, x: &GF2Element<DIM>) -> Result<(), PointValidationError> {
+        if x != &GF2Element::ZERO {
+            let beta = x.add(self.curve.a()).add(&self.curve.b().mul(&x.sqr().inverse()));
+            if beta.trace() != GF2Element::ZERO {
+                return Err(PointValidationError::OnTwist);
+            }
+        }
+        if self.curve.ladder_x(x, &self.order).is_some() {
+            return Err(PointValidationError::WrongSubgroup);
+        }
+        Ok(())
+    }
+
+    /// kP through the Montgomery ladder with the chosen countermeasures, the result doesn't depend on them
+    pub fn hardened_mul<R: RngCore>(&self, p: &Point, k: &UnsignedLongInt, countermeasures: Countermeasures, rng: &mut R) -> Result<Point, PointValidationError> {
+        if countermeasures.point_validation {
+            self.validate_point(p)?;
+        }
+        let k = self.maybe_blind(k, countermeasures, rng);
+        let z = random_z(countermeasures, rng);
+        Ok(self.curve.ladder_mul_with_z(p, &k, &z))
+    }
+
+    /// x(kP) from x(P) alone, None for the point at infinity
+    pub fn hardened_mul_x<R: RngCore>(&self, x: &GF2Element<DIM>, k: &UnsignedLongInt, countermeasures: Countermeasures, rng: &mut R) -> Result<Option<GF2Element<DIM>>, PointValidationError> {
+        if countermeasures.point_validation {
+            self.validate_x(x)?;
+        }
+        let k = self.maybe_blind(k, countermeasures, rng);
+        let z = random_z(countermeasures, rng);
+        Ok(self.curve.ladder_x_with_z(x, &k, &z))
+    }
+
+    // k + r n with the top bit of r set, so the blinded scalars have nearly the same length
+    fn maybe_blind<R: RngCore>(&self, k: &UnsignedLongInt, countermeasures: Countermeasures, rng: &mut R) -> UnsignedLongInt {
+        if !countermeasures.scalar_blinding {
+            return k.clone();
+        }
+        let mut words: Vec<u64> = (0..BLINDING_BITS.div_ceil(64)).map(|_| rng.next_u64()).collect();
+        words[0] |= 1 << ((BLINDING_BITS - 1) % 64);
+        k.add(&from_words(&words).mul(&self.order))
+    }
+}
+
+fn random_z<R: RngCore>(countermeasures: Countermeasures, rng: &mut R) -> GF2Element<DIM> {
+    if !countermeasures.randomized_coordinates {
+        return GF2Element::ONE;
+    }
+    loop {
+        let z = GF2Element::random(rng);
+        if z != GF2Element::ZERO {
+            return z;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::curve::Curve;
+    use crate::curve::tests::point_after;
+    use crate::domain::tests::test_domain;
+    use crate::MOD_DEG;
+    use super::*;
+
+    fn all_combinations() -> impl Iterator<Item=Countermeasures> {
+        (0..8).map(|i| Countermeasures {
+            scalar_blinding: i & 1 != 0,
+            randomized_coordinates: i & 2 != 0,
+            point_validation: i & 4 != 0,
+        })
+    }
+
+    #[test]
+    fn unchanged_result_test() -> Result<(), Box<dyn Error>> {
+        let domain = test_domain()?;
+        let mut rng = rand::thread_rng();
+        let k = UnsignedLongInt::from(rand::random::<u64>()).mul(&UnsignedLongInt::from(rand::random::<u64>()));
+        let q = domain.curve.ld_mul(&domain.base, &UnsignedLongInt::from(12345));
+
+        let expected = domain.curve.ld_mul(&q, &k);
+        for countermeasures in all_combinations() {
+            assert_eq!(domain.hardened_mul(&q, &k, countermeasures, &mut rng)?, expected);
+            assert_eq!(domain.hardened_mul_x(q.x().unwrap(), &k, countermeasures, &mut rng)?.as_ref(), expected.x());
+        }
+        assert_eq!(domain.hardened_mul(&q, &domain.order, Countermeasures::ALL, &mut rng)?, Point::Infinity);
+
+        Ok(())
+    }
+
+    #[test]
+    fn randomization_test() -> Result<(), Box<dyn Error>> {
+        let domain = test_domain()?;
+        let mut rng = rand::thread_rng();
+        let k = UnsignedLongInt::from(7);
+
+        // the intermediate values differ between runs, the result doesn't
+        let blinded = domain.maybe_blind(&k, Countermeasures::ALL, &mut rng);
+        assert_ne!(blinded, domain.maybe_blind(&k, Countermeasures::ALL, &mut rng));
+        let top = domain.order.get_highest_set_bit().unwrap() + BLINDING_BITS;
+        assert!((top - 1..=top).contains(&blinded.get_highest_set_bit().unwrap()));
+        assert_eq!(domain.maybe_blind(&k, Countermeasures::NONE, &mut rng), k);
+        assert_ne!(random_z(Countermeasures::ALL, &mut rng), GF2Element::ONE);
+
+        let x = domain.base.x().unwrap();
+        let z = random_z(Countermeasures::ALL, &mut rng);
+        assert_eq!(domain.curve.ladder_x_with_z(x, &k, &z), domain.curve.ladder_x(x, &k));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validation_test() -> Result<(), Box<dyn Error>> {
+        let domain = test_domain()?;
+        let mut rng = rand::thread_rng();
+        let k = UnsignedLongInt::from(1000);
+        let with_validation = Countermeasures::ALL;
+        let without_validation = Countermeasures { point_validation: false, ..Countermeasures::ALL };
+
+        // invalid curve: a point of y^2 + xy = x^3 + x^2 + b' for another b'
+        let mut other_b = domain.curve.b().clone();
+        other_b.data[0] ^= 2;
+        let other = Curve::new(GF2Element::ONE, other_b)?;
+        let invalid = point_after(&other, 2);
+        assert_eq!(domain.hardened_mul(&invalid, &k, with_validation, &mut rng), Err(PointValidationError::NotOnCurve));
+        assert!(domain.hardened_mul(&invalid, &k, without_validation, &mut rng).is_ok());
+
+        // small subgroup: adding the point of order 2
+        let t = domain.curve.point(GF2Element::ZERO, domain.curve.b().frobenius(MOD_DEG - 1))?;
+        let outside = domain.curve.add(&domain.base, &t);
+        assert_eq!(domain.hardened_mul(&outside, &k, with_validation, &mut rng), Err(PointValidationError::WrongSubgroup));
+        assert_eq!(domain.validate_x(outside.x().unwrap()), Err(PointValidationError::WrongSubgroup));
+        assert_eq!(domain.hardened_mul(&Point::Infinity, &k, with_validation, &mut rng), Err(PointValidationError::Infinity));
+
+        // twist: an x without a point on the curve
+        let mut x = GF2Element::ZERO;
+        for v in 2.. {
+            x.data[0] = v;
+            if domain.validate_x(&x) == Err(PointValidationError::OnTwist) {
+                break;
+            }
+        }
+        assert_eq!(domain.hardened_mul_x(&x, &k, with_validation, &mut rng), Err(PointValidationError::OnTwist));
+        assert!(domain.hardened_mul_x(&x, &k, without_validation, &mut rng).is_ok());
+
+        Ok(())
+    }
+}
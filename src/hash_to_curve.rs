@@ -0,0 +1,316 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::curve::{Curve, Point};
+use crate::domain::DomainParameters;
+use crate::field::batch_inverse;
+use crate::ladder::conditional_swap_elements;
+use crate::solve_sq_eq::{halftrace, solve};
+use crate::{GF2Element, DIM, MOD_DEG};
+
+/// Security level k of `hash_to_field`
+pub const SECURITY_BITS: usize = 128;
+
+/// Uniform bytes per field element: m + k bits reduced modulo f(x) are within 2^-k of uniform
+pub const FIELD_HASH_BYTES: usize = (MOD_DEG + SECURITY_BITS).div_ceil(8);
+
+/// x-coordinates tried by `map_to_curve`, all of them fail with probability about 2^-64
+pub const MAP_CANDIDATES: usize = 64;
+
+/// Counter values tried by `try_and_increment`
+pub const MAX_INCREMENTS: usize = 256;
+
+const WORD: usize = usize::BITS as usize;
+
+// the oversized buffer has to fit into From<[usize; 2 * DIM]>
+const _: () = assert!(8 * FIELD_HASH_BYTES <= 2 * DIM * WORD);
+
+/// Hash function behind `expand_message_xmd`
+pub trait HashFunction {
+    /// Output size, b_in_bytes in RFC 9380
+    const OUTPUT_BYTES: usize;
+    /// Input block size, s_in_bytes in RFC 9380
+    const BLOCK_BYTES: usize;
+
+    fn hash(data: &[u8]) -> Vec<u8>;
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HashToCurveError {
+    LengthTooLarge,
+    NotFound,
+}
+
+impl Display for HashToCurveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LengthTooLarge => write!(f, "expand_message_xmd can't produce that many bytes"),
+            Self::NotFound => write!(f, "no candidate x-coordinate has a point on the curve"),
+        }
+    }
+}
+
+impl Error for HashToCurveError {}
+
+/// expand_message_xmd of RFC 9380 5.3.1, a domain separation tag over 255 bytes is hashed first
+pub fn expand_message_xmd<H: HashFunction>(msg: &[u8], dst: &[u8], len: usize) -> Result<Vec<u8>, HashToCurveError> {
+    let ell = len.div_ceil(H::OUTPUT_BYTES);
+    if ell > 255 || len > 0xffff {
+        return Err(HashToCurveError::LengthTooLarge);
+    }
+
+    let mut dst_prime = if dst.len() > 255 { H::hash(&[b"H2C-OVERSIZE-DST-", dst].concat()) } else { dst.to_vec() };
+    dst_prime.push(dst_prime.len() as u8);
+
+    // b_0 = H(Z_pad || msg || I2OSP(len, 2) || I2OSP(0, 1) || DST_prime)
+    let mut input = vec![0; H::BLOCK_BYTES];
+    input.extend_from_slice(msg);
+    input.extend_from_slice(&(len as u16).to_be_bytes());
+    input.push(0);
+    input.extend_from_slice(&dst_prime);
+    let b0 = H::hash(&input);
+
+    // b_i = H((b_0 xor b_(i-1)) || I2OSP(i, 1) || DST_prime)
+    let mut b = H::hash(&[b0.as_slice(), &[1], &dst_prime].concat());
+    let mut out = b.clone();
+    for i in 2..=ell {
+        let mixed: Vec<u8> = b0.iter().zip(b.iter()).map(|(x, y)| x ^ y).collect();
+        b = H::hash(&[mixed.as_slice(), &[i as u8], &dst_prime].concat());
+        out.extend_from_slice(&b);
+    }
+    out.truncate(len);
+    Ok(out)
+}
+
+/// `count` field elements, each from FIELD_HASH_BYTES bytes of expand_message_xmd
+pub fn hash_to_field<H: HashFunction>(msg: &[u8], dst: &[u8], count: usize) -> Result<Vec<GF2Element<DIM>>, HashToCurveError> {
+    let uniform = expand_message_xmd::<H>(msg, dst, count * FIELD_HASH_BYTES)?;
+    Ok(uniform.chunks(FIELD_HASH_BYTES).map(reduce_bytes).collect())
+}
+
+// Big-endian bytes as a polynomial of degree below 2 * DIM * WORD, reduced modulo f(x)
+fn reduce_bytes(bytes: &[u8]) -> GF2Element<DIM> {
+    let mut words = [0usize; 2 * DIM];
+    for (j, byte) in bytes.iter().rev().enumerate() {
+        let bit = 8 * j;
+        words[bit / WORD] |= (*byte as usize) << (bit % WORD);
+    }
+    GF2Element::from(words)
+}
+
+impl Curve {
+    /// x from hash_to_field(msg || ctr) for ctr = 0, 1, ... until y^2 + xy = x^3 + a x^2 + b
+    /// has a solution, a second hashed element picks one of the two roots.
+    /// The running time reveals the number of attempts.
+    pub fn try_and_increment<H: HashFunction>(&self, msg: &[u8], dst: &[u8]) -> Result<Point, HashToCurveError> {
+        for ctr in 0..MAX_INCREMENTS {
+            let elements = hash_to_field::<H>(&[msg, &[ctr as u8]].concat(), dst, 2)?;
+            let (x, sign) = (&elements[0], &elements[1]);
+            if x == &GF2Element::ZERO {
+                continue;
+            }
+
+            let rhs = x.sqr().mul(&x.add(self.a())).add(self.b());
+            if let Ok((y0, y1)) = solve(x, &rhs) {
+                let y = if sign.data[0] & 1 == 0 { y0 } else { y1 };
+                return Ok(Point::Affine { x: x.clone(), y });
+            }
+        }
+        Err(HashToCurveError::NotFound)
+    }
+
+    /// Deterministic map of u_1, ..., u_n, s to a point with the same operations for every input:
+    /// each nonzero u_i is tried as x with y = x z, z^2 + z = x + a + b / x^2, one batched inversion for all,
+    /// the first solvable one is kept through masks and the low bit of s picks z or z + 1
+    pub fn map_to_curve(&self, u: &[GF2Element<DIM>]) -> Option<Point> {
+        let (sign, candidates) = u.split_last()?;
+
+        // zeros are replaced by 1 for the batched inversion and never selected
+        let xs: Vec<_> = candidates.iter()
+            .map(|x| if x == &GF2Element::ZERO { GF2Element::ONE } else { x.clone() })
+            .collect();
+        let inverses = batch_inverse(&xs.iter().map(|x| x.sqr()).collect::<Vec<_>>());
+
+        let mut found = false;
+        let mut x_out = GF2Element::ZERO;
+        let mut z_out = GF2Element::ZERO;
+        for ((x, u), inv) in xs.into_iter().zip(candidates).zip(inverses) {
+            let c = x.add(self.a()).add(&self.b().mul(&inv));
+            let valid = (c.trace() == GF2Element::ZERO) & (u != &GF2Element::ZERO);
+            let (mut x, mut z) = (x, halftrace(&c));
+
+            let take = valid & !found;
+            conditional_swap_elements(&mut x_out, &mut x, take);
+            conditional_swap_elements(&mut z_out, &mut z, take);
+            found |= valid;
+        }
+        if !found {
+            return None;
+        }
+
+        // z and z + 1 differ in the lowest bit
+        z_out.data[0] ^= (z_out.data[0] ^ sign.data[0]) & 1;
+        let y = x_out.mul(&z_out);
+        Some(Point::Affine { x: x_out, y })
+    }
+
+    /// map_to_curve of MAP_CANDIDATES + 1 elements of hash_to_field. The point lies on the curve
+    /// but not necessarily in the subgroup of prime order, `DomainParameters::hash_to_curve` clears
+    /// the cofactor.
+    pub fn hash_to_curve<H: HashFunction>(&self, msg: &[u8], dst: &[u8]) -> Result<Point, HashToCurveError> {
+        let u = hash_to_field::<H>(msg, dst, MAP_CANDIDATES + 1)?;
+        self.map_to_curve(&u).ok_or(HashToCurveError::NotFound)
+    }
+}
+
+impl DomainParameters {
+    /// hash_to_curve of RFC 9380: `Curve::hash_to_curve` followed by clear_cofactor, the point
+    /// lies in the subgroup of order n
+    pub fn hash_to_curve<H: HashFunction>(&self, msg: &[u8], dst: &[u8]) -> Result<Point, HashToCurveError> {
+        let p = self.curve.hash_to_curve::<H>(msg, dst)?;
+        Ok(self.clear_cofactor(&p))
+    }
+
+    /// clear_cofactor of RFC 9380 with h_eff = h
+    pub fn clear_cofactor(&self, p: &Point) -> Point {
+        self.curve.ld_mul(p, &self.cofactor)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::error::Error;
+    use sha2::{Digest, Sha256};
+    use crate::curve::tests::{point_after, test_curve};
+    use crate::domain::tests::test_domain;
+    use super::*;
+
+    pub(crate) struct Sha256Hash;
+
+    impl HashFunction for Sha256Hash {
+        const OUTPUT_BYTES: usize = 32;
+        const BLOCK_BYTES: usize = 64;
+
+        fn hash(data: &[u8]) -> Vec<u8> {
+            Sha256::digest(data).to_vec()
+        }
+    }
+
+    const DST: &[u8] = b"QUUX-V01-CS02-with-expander-SHA256-128";
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn expand_message_test() -> Result<(), Box<dyn Error>> {
+        // RFC 9380 K.1
+        let vectors = [
+            (b"".as_slice(), "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"),
+            (b"abc".as_slice(), "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"),
+            (b"abcdef0123456789".as_slice(), "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1"),
+        ];
+        for (msg, expected) in vectors {
+            assert_eq!(hex(&expand_message_xmd::<Sha256Hash>(msg, DST, 32)?), expected);
+        }
+        // len_in_bytes = 0x80 takes four blocks
+        let long_vectors = [
+            (b"".as_slice(), concat!(
+                "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbe",
+                "e0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18",
+                "eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dc",
+                "c541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced",
+            )),
+            (b"abc".as_slice(), concat!(
+                "abba86a6129e366fc877aab32fc4ffc70120d8996c88aee2fe4b32d6c7b6437a",
+                "647e6c3163d40b76a73cf6a5674ef1d890f95b664ee0afa5359a5c4e07985635",
+                "bbecbac65d747d3d2da7ec2b8221b17b0ca9dc8a1ac1c07ea6a1e60583e2cb00",
+                "058e77b7b72a298425cd1b941ad4ec65e8afc50303a22c0f99b0509b4c895f40",
+            )),
+        ];
+        for (msg, expected) in long_vectors {
+            assert_eq!(hex(&expand_message_xmd::<Sha256Hash>(msg, DST, 0x80)?), expected);
+        }
+
+        assert_eq!(expand_message_xmd::<Sha256Hash>(b"", DST, 255 * 32 + 1), Err(HashToCurveError::LengthTooLarge));
+        let oversized = [7; 300];
+        assert_ne!(expand_message_xmd::<Sha256Hash>(b"", &oversized, 32)?, expand_message_xmd::<Sha256Hash>(b"", &oversized[..255], 32)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn hash_to_field_test() -> Result<(), Box<dyn Error>> {
+        let elements = hash_to_field::<Sha256Hash>(b"abc", DST, 2)?;
+        let uniform = expand_message_xmd::<Sha256Hash>(b"abc", DST, 2 * FIELD_HASH_BYTES)?;
+
+        assert_eq!(elements.len(), 2);
+        assert_eq!(elements[1], reduce_bytes(&uniform[FIELD_HASH_BYTES..]));
+        assert!(elements.iter().all(|el| el.deg() < MOD_DEG));
+        assert_ne!(elements[0], elements[1]);
+        assert_ne!(hash_to_field::<Sha256Hash>(b"abc", b"other tag", 1)?[0], elements[0]);
+
+        // the low bytes land in the low word
+        let mut bytes = vec![0; FIELD_HASH_BYTES];
+        bytes[FIELD_HASH_BYTES - 1] = 0x2a;
+        assert_eq!(reduce_bytes(&bytes).data[0], 0x2a);
+
+        Ok(())
+    }
+
+    #[test]
+    fn try_and_increment_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let p = curve.try_and_increment::<Sha256Hash>(b"abc", DST)?;
+
+        assert!(curve.is_on_curve(&p) && !p.is_infinity());
+        assert_eq!(curve.try_and_increment::<Sha256Hash>(b"abc", DST)?, p);
+        assert_ne!(curve.try_and_increment::<Sha256Hash>(b"abd", DST)?, p);
+
+        Ok(())
+    }
+
+    #[test]
+    fn map_to_curve_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let p = curve.hash_to_curve::<Sha256Hash>(b"abc", DST)?;
+
+        assert!(curve.is_on_curve(&p) && !p.is_infinity());
+        assert_eq!(curve.hash_to_curve::<Sha256Hash>(b"abc", DST)?, p);
+        assert_ne!(curve.hash_to_curve::<Sha256Hash>(b"abd", DST)?, p);
+
+        // zero and x-coordinates of the twist are skipped, the last element picks the root
+        let q = point_after(&curve, 2);
+        let twist = (2..).map(|k| GF2Element::from([k; 2 * DIM]))
+            .find(|x| x.add(curve.a()).add(&curve.b().mul(&x.sqr().inverse())).trace() == GF2Element::ONE)
+            .unwrap();
+        let x = q.x().unwrap().clone();
+        let even = curve.map_to_curve(&[GF2Element::ZERO, twist.clone(), x.clone(), GF2Element::ZERO]).unwrap();
+        let odd = curve.map_to_curve(&[GF2Element::ZERO, twist.clone(), x.clone(), GF2Element::ONE]).unwrap();
+        assert_eq!(even.x(), Some(&x));
+        assert!(curve.is_on_curve(&even) && curve.is_on_curve(&odd));
+        assert_eq!(odd, curve.neg(&even));
+
+        assert_eq!(curve.map_to_curve(&[GF2Element::ZERO, twist, GF2Element::ONE]), None);
+        assert_eq!(curve.map_to_curve(&[]), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cofactor_test() -> Result<(), Box<dyn Error>> {
+        // #E = 4 n, so about three of four messages map outside the subgroup
+        let domain = test_domain()?;
+        let outside = |msg: &[u8]| {
+            let p = domain.curve.hash_to_curve::<Sha256Hash>(msg, DST).unwrap();
+            domain.curve.ld_mul(&p, &domain.order) != Point::Infinity
+        };
+        let msg = (0u8..).map(|i| [b'm', i]).find(|msg| outside(msg)).unwrap();
+
+        let p = domain.hash_to_curve::<Sha256Hash>(&msg, DST)?;
+        assert!(domain.curve.is_on_curve(&p) && !p.is_infinity());
+        assert_eq!(domain.curve.ld_mul(&p, &domain.order), Point::Infinity);
+        assert_eq!(p, domain.curve.ld_mul(&domain.curve.hash_to_curve::<Sha256Hash>(&msg, DST)?, &domain.cofactor));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,361 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use vl_big_ints::UnsignedLongInt;
+use crate::poly::Poly;
+use crate::{GF2Element, DIM};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HyperellipticError {
+    WrongDegree,
+    Singular,
+    InvalidDivisor,
+}
+
+impl Display for HyperellipticError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WrongDegree => write!(f, "genus 2 curve needs deg h <= 2 and a monic f of degree 5"),
+            Self::Singular => write!(f, "curve is singular"),
+            Self::InvalidDivisor => write!(f, "(u, v) is not a reduced divisor of the curve"),
+        }
+    }
+}
+
+impl Error for HyperellipticError {}
+
+/// Genus 2 curve y^2 + h(x) y = f(x) with one point at infinity
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HyperellipticCurve {
+    h: Poly<GF2Element<DIM>>,
+    f: Poly<GF2Element<DIM>>,
+}
+
+/// Divisor class in Mumford representation [u, v]: monic u of degree at most 2,
+/// deg v < deg u and u | v^2 + h v + f. The neutral element is [1, 0].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Divisor {
+    u: Poly<GF2Element<DIM>>,
+    v: Poly<GF2Element<DIM>>,
+}
+
+impl Divisor {
+    pub fn zero() -> Self {
+        Self { u: Poly::one(), v: Poly::zero() }
+    }
+
+    pub fn u(&self) -> &Poly<GF2Element<DIM>> {
+        &self.u
+    }
+
+    pub fn v(&self) -> &Poly<GF2Element<DIM>> {
+        &self.v
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.u.degree() == Some(0)
+    }
+}
+
+impl HyperellipticCurve {
+    pub fn new(h: Poly<GF2Element<DIM>>, f: Poly<GF2Element<DIM>>) -> Result<Self, HyperellipticError> {
+        if h.degree().is_some_and(|d| d > 2) || f.degree() != Some(5) || f.leading() != Some(&GF2Element::ONE) {
+            return Err(HyperellipticError::WrongDegree);
+        }
+        // A singular point has h(x) = 0 and h'(x) y = f'(x), y^2 = f(x), so it exists
+        // exactly when h and f'^2 + h'^2 f share a root. In characteristic 2 h = 0 is always singular.
+        let condition = f.derivative().sqr().add(&h.derivative().sqr().mul(&f));
+        if h.is_zero() || Poly::gcd(&h, &condition).degree() != Some(0) {
+            return Err(HyperellipticError::Singular);
+        }
+        Ok(Self { h, f })
+    }
+
+    pub fn h(&self) -> &Poly<GF2Element<DIM>> {
+        &self.h
+    }
+
+    pub fn f(&self) -> &Poly<GF2Element<DIM>> {
+        &self.f
+    }
+
+    pub fn is_valid(&self, d: &Divisor) -> bool {
+        let Some(deg) = d.u.degree() else {
+            return false;
+        };
+        deg <= 2 && d.u.leading() == Some(&GF2Element::ONE) && d.v.degree().is_none_or(|v_deg| v_deg < deg)
+            && d.v.sqr().add(&self.h.mul(&d.v)).add(&self.f).rem(&d.u).is_zero()
+    }
+
+    /// Divisor after checking the Mumford conditions
+    pub fn divisor(&self, u: Poly<GF2Element<DIM>>, v: Poly<GF2Element<DIM>>) -> Result<Divisor, HyperellipticError> {
+        let d = Divisor { u, v };
+        if self.is_valid(&d) { Ok(d) } else { Err(HyperellipticError::InvalidDivisor) }
+    }
+
+    /// Class of P - infinity for the affine point P = (x, y), that is [x + x_P, y_P]
+    pub fn from_point(&self, x: GF2Element<DIM>, y: GF2Element<DIM>) -> Result<Divisor, HyperellipticError> {
+        self.divisor(Poly::new(vec![x, GF2Element::ONE]), Poly::constant(y))
+    }
+
+    /// -[u, v] = [u, (v + h) mod u]
+    pub fn neg(&self, d: &Divisor) -> Divisor {
+        Divisor { u: d.u.clone(), v: d.v.add(&self.h).rem(&d.u) }
+    }
+
+    /// Cantor's algorithm: composition through two extended gcds, then reduction
+    pub fn cantor_add(&self, d1: &Divisor, d2: &Divisor) -> Divisor {
+        let (e, e1, e2) = Poly::xgcd(&d1.u, &d2.u);
+        let (d, c1, c2) = Poly::xgcd(&e, &d1.v.add(&d2.v).add(&self.h));
+        let (s1, s2, s3) = (c1.mul(&e1), c1.mul(&e2), c2);
+
+        let mut u = d1.u.mul(&d2.u).div_rem(&d.sqr()).0;
+        let v = s1.mul(&d1.u).mul(&d2.v)
+            .add(&s2.mul(&d2.u).mul(&d1.v))
+            .add(&s3.mul(&d1.v.mul(&d2.v).add(&self.f)));
+        let mut v = v.div_rem(&d).0.rem(&u);
+
+        while u.degree().is_some_and(|deg| deg > 2) {
+            u = self.f.add(&self.h.mul(&v)).add(&v.sqr()).div_rem(&u).0;
+            v = self.h.add(&v).rem(&u);
+        }
+        Divisor { u: u.monic(), v }
+    }
+
+    /// Explicit formulas in the style of Lange for two divisors of degree 2 with coprime u,
+    /// Cantor's algorithm for the rest
+    pub fn add(&self, d1: &Divisor, d2: &Divisor) -> Divisor {
+        if d1 == d2 {
+            return self.double(d1);
+        }
+        self.add_explicit(d1, d2).unwrap_or_else(|| self.cantor_add(d1, d2))
+    }
+
+    pub fn double(&self, d: &Divisor) -> Divisor {
+        self.double_explicit(d).unwrap_or_else(|| self.cantor_add(d, d))
+    }
+
+    /// Left-to-right double-and-add
+    pub fn mul(&self, d: &Divisor, k: &UnsignedLongInt) -> Divisor {
+        let mut out = Divisor::zero();
+        let Some(top) = k.get_highest_set_bit() else {
+            return out;
+        };
+        for i in (0..top + 1).rev() {
+            out = self.double(&out);
+            if k.get_bit(i) {
+                out = self.add(&out, d);
+            }
+        }
+        out
+    }
+
+    // s = r^-1 (s1' x + s0') = (v1 + v2) / u2 mod u1 through the resultant r of u1 and u2,
+    // None when r = 0 or s is a constant
+    fn add_explicit(&self, d1: &Divisor, d2: &Divisor) -> Option<Divisor> {
+        if d1.u.degree() != Some(2) || d2.u.degree() != Some(2) {
+            return None;
+        }
+        let (u10, u11) = (d1.u.coef(0), d1.u.coef(1));
+        let (u20, u21) = (d2.u.coef(0), d2.u.coef(1));
+
+        // u2 mod u1 = z1 x + z2, its inverse is (z1 x + z3) / r
+        let z1 = u11.add(&u21);
+        let z2 = u10.add(&u20);
+        let z3 = u11.mul(&z1).add(&z2);
+        let r = z2.mul(&z3).add(&z1.sqr().mul(&u10));
+
+        let w0 = d1.v.coef(0).add(&d2.v.coef(0));
+        let w1 = d1.v.coef(1).add(&d2.v.coef(1));
+        let (s1, s0) = mul_mod(&w1, &w0, &z1, &z3, &u11, &u10);
+        self.compose(d1, d2, &r, &s1, &s0)
+    }
+
+    // s = k / (h mod u) mod u for k = (f + h v + v^2) / u
+    fn double_explicit(&self, d: &Divisor) -> Option<Divisor> {
+        if d.u.degree() != Some(2) {
+            return None;
+        }
+        let (u0, u1) = (d.u.coef(0), d.u.coef(1));
+        let (v0, v1) = (d.v.coef(0), d.v.coef(1));
+        let (h0, h1, h2) = (self.h.coef(0), self.h.coef(1), self.h.coef(2));
+
+        // h mod u = a x + b, its inverse is (a x + b + a u1) / r
+        let a = h1.add(&h2.mul(&u1));
+        let b = h0.add(&h2.mul(&u0));
+        let inv0 = b.add(&a.mul(&u1));
+        let r = u0.mul(&a.sqr()).add(&b.mul(&inv0));
+
+        // f + h v + v^2 = x^5 + f4 x^4 + c3 x^3 + c2 x^2 + ..., quotient x^3 + q2 x^2 + q1 x + q0
+        let c3 = self.f.coef(3).add(&h2.mul(&v1));
+        let c2 = self.f.coef(2).add(&h2.mul(&v0)).add(&h1.mul(&v1)).add(&v1.sqr());
+        let q2 = self.f.coef(4).add(&u1);
+        let q1 = c3.add(&u0).add(&u1.mul(&q2));
+        let q0 = c2.add(&u0.mul(&q2)).add(&u1.mul(&q1));
+        // k mod u with x^3 = (u1^2 + u0) x + u1 u0 mod u
+        let k1 = u1.sqr().add(&u0).add(&q2.mul(&u1)).add(&q1);
+        let k0 = u1.mul(&u0).add(&q2.mul(&u0)).add(&q0);
+
+        let (s1, s0) = mul_mod(&k1, &k0, &a, &inv0, &u1, &u0);
+        self.compose(d, d, &r, &s1, &s0)
+    }
+
+    // With s = (s1' x + s0') / r, l = s u2 + v2 meets v1 mod u1 and v2 mod u2, so
+    // u = (f + h l + l^2) / (u1 u2) = (s^2 u2 + s h + k2) / u1 with k2 = (f + h v2 + v2^2) / u2
+    // and v = (h + l) mod u. A single inversion of r s1'.
+    fn compose(&self, d1: &Divisor, d2: &Divisor, r: &GF2Element<DIM>, s1: &GF2Element<DIM>, s0: &GF2Element<DIM>) -> Option<Divisor> {
+        if *r == GF2Element::ZERO || *s1 == GF2Element::ZERO {
+            return None;
+        }
+        let (u10, u11) = (d1.u.coef(0), d1.u.coef(1));
+        let (u20, u21) = (d2.u.coef(0), d2.u.coef(1));
+        let (v20, v21) = (d2.v.coef(0), d2.v.coef(1));
+        let (h0, h1, h2) = (self.h.coef(0), self.h.coef(1), self.h.coef(2));
+
+        let t = r.mul(s1).inverse();
+        let s0 = s0.mul(s1).mul(&t);
+        let s1 = s1.sqr().mul(&t);
+        // 1 / s1^2 = r^4 t^2
+        let lead_inv = r.sqr().sqr().mul(&t.sqr());
+
+        // s^2 u2 + s h + k2 = p4 x^4 + p3 x^3 + p2 x^2 + ..., divided by u1
+        let p4 = s1.sqr();
+        let p3 = p4.mul(&u21).add(&s1.mul(&h2)).add(&GF2Element::ONE);
+        let p2 = p4.mul(&u20).add(&s0.sqr()).add(&s1.mul(&h1)).add(&s0.mul(&h2))
+            .add(&self.f.coef(4)).add(&u21);
+        let q1 = p3.add(&p4.mul(&u11));
+        let q0 = p2.add(&q1.mul(&u11)).add(&p4.mul(&u10));
+        let n1 = q1.mul(&lead_inv);
+        let n0 = q0.mul(&lead_inv);
+
+        // h + l = l3 x^3 + l2 x^2 + l1 x + l0 reduced with x^2 = n1 x + n0
+        let l3 = s1.clone();
+        let l2 = s1.mul(&u21).add(&s0).add(&h2);
+        let l1 = s1.mul(&u20).add(&s0.mul(&u21)).add(&v21).add(&h1);
+        let l0 = s0.mul(&u20).add(&v20).add(&h0);
+        let v1 = l3.mul(&n1.sqr().add(&n0)).add(&l2.mul(&n1)).add(&l1);
+        let v0 = l3.mul(&n1.mul(&n0)).add(&l2.mul(&n0)).add(&l0);
+
+        Some(Divisor {
+            u: Poly::new(vec![n0, n1, GF2Element::ONE]),
+            v: Poly::new(vec![v0, v1]),
+        })
+    }
+}
+
+// (a1 x + a0)(b1 x + b0) mod x^2 + u1 x + u0 with Karatsuba, returns (c1, c0)
+fn mul_mod(a1: &GF2Element<DIM>, a0: &GF2Element<DIM>, b1: &GF2Element<DIM>, b0: &GF2Element<DIM>, u1: &GF2Element<DIM>, u0: &GF2Element<DIM>) -> (GF2Element<DIM>, GF2Element<DIM>) {
+    let w0 = a0.mul(b0);
+    let w1 = a1.mul(b1);
+    let c1 = a0.add(a1).mul(&b0.add(b1)).add(&w0).add(&w1.mul(&u1.add(&GF2Element::ONE)));
+    let c0 = w0.add(&w1.mul(u0));
+    (c1, c0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::solve_sq_eq::halftrace;
+    use super::*;
+
+    fn element(k: usize) -> GF2Element<DIM> {
+        GF2Element::from([k; 2 * DIM])
+    }
+
+    // y^2 + (x^2 + x + 1) y = x^5 + x^3 + e3 x^2 + e5
+    fn test_curve() -> Result<HyperellipticCurve, Box<dyn Error>> {
+        let h = Poly::new(vec![GF2Element::ONE, GF2Element::ONE, GF2Element::ONE]);
+        let f = Poly::new(vec![element(5), GF2Element::ZERO, element(3), GF2Element::ONE, GF2Element::ZERO, GF2Element::ONE]);
+        Ok(HyperellipticCurve::new(h, f)?)
+    }
+
+    // Degree 1 divisors of the points with x = [k; 2 * DIM] mod f(x) from k = start:
+    // y = h(x) z with z^2 + z = f(x) / h(x)^2
+    fn points(curve: &HyperellipticCurve, start: usize, count: usize) -> Vec<Divisor> {
+        (start..).filter_map(|k| {
+            let x = element(k);
+            let hx = curve.h().eval(&x);
+            if hx == GF2Element::ZERO {
+                return None;
+            }
+            let c = curve.f().eval(&x).mul(&hx.sqr().inverse());
+            if c.trace() != GF2Element::ZERO {
+                return None;
+            }
+            curve.from_point(x, hx.mul(&halftrace(&c))).ok()
+        }).take(count).collect()
+    }
+
+    // Degree 2 divisors P + Q - 2 infinity
+    fn divisors(curve: &HyperellipticCurve, count: usize) -> Vec<Divisor> {
+        points(curve, 2, 2 * count).chunks(2).map(|pq| curve.cantor_add(&pq[0], &pq[1])).collect()
+    }
+
+    #[test]
+    fn cantor_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let ds = divisors(&curve, 3);
+        let (a, b, c) = (&ds[0], &ds[1], &ds[2]);
+
+        assert!(ds.iter().all(|d| curve.is_valid(d) && d.u().degree() == Some(2)));
+        assert_eq!(curve.cantor_add(a, &Divisor::zero()), *a);
+        assert!(curve.cantor_add(a, &curve.neg(a)).is_zero());
+        assert_eq!(curve.cantor_add(a, b), curve.cantor_add(b, a));
+        assert_eq!(curve.cantor_add(&curve.cantor_add(a, b), c), curve.cantor_add(a, &curve.cantor_add(b, c)));
+        assert!(curve.is_valid(&curve.cantor_add(a, a)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn explicit_formulas_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let ds = divisors(&curve, 3);
+
+        for (a, b) in [(&ds[0], &ds[1]), (&ds[1], &ds[2])] {
+            let sum = curve.add_explicit(a, b).expect("u1 and u2 of random divisors are coprime");
+            assert_eq!(sum, curve.cantor_add(a, b));
+            let double = curve.double_explicit(a).expect("s is not a constant for a random divisor");
+            assert_eq!(double, curve.cantor_add(a, a));
+        }
+
+        // the special cases go to Cantor's algorithm
+        let p = &points(&curve, 2, 1)[0];
+        assert_eq!(curve.add(p, &ds[0]), curve.cantor_add(p, &ds[0]));
+        assert!(curve.add(&ds[0], &curve.neg(&ds[0])).is_zero());
+        assert_eq!(curve.double(&Divisor::zero()), Divisor::zero());
+
+        Ok(())
+    }
+
+    #[test]
+    fn scalar_mul_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let d = &divisors(&curve, 1)[0];
+
+        let mut expected = Divisor::zero();
+        for k in 0..5 {
+            assert_eq!(curve.mul(d, &UnsignedLongInt::from(k)), expected);
+            expected = curve.cantor_add(&expected, d);
+        }
+        let (k, l) = (rand::random::<u8>() as u64, rand::random::<u8>() as u64);
+        let sum = curve.add(&curve.mul(d, &UnsignedLongInt::from(k)), &curve.mul(d, &UnsignedLongInt::from(l)));
+        assert_eq!(curve.mul(d, &UnsignedLongInt::from(k + l)), sum);
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let x = Poly::monomial(GF2Element::ONE, 1);
+        let x5 = Poly::monomial(GF2Element::ONE, 5);
+
+        assert_eq!(HyperellipticCurve::new(curve.h().clone(), x5.add(&x5.mul(&x))), Err(HyperellipticError::WrongDegree));
+        assert_eq!(HyperellipticCurve::new(Poly::zero(), curve.f().clone()), Err(HyperellipticError::Singular));
+        // y^2 + x y = x^5 + x^2 is singular at (0, 0)
+        assert_eq!(HyperellipticCurve::new(x.clone(), x5.add(&x.sqr())), Err(HyperellipticError::Singular));
+        assert_eq!(curve.divisor(x.clone(), Poly::constant(GF2Element::ONE)), Err(HyperellipticError::InvalidDivisor));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,387 @@
+use vl_big_ints::UnsignedLongInt;
+use crate::curve::{Curve, CurveError, Point};
+use crate::long_int::SignedLongInt;
+use crate::lopez_dahab::LdPoint;
+use crate::{GF2Element, MOD_DEG};
+
+// Element r0 + r1 tau of Z[tau] with tau^2 = mu tau - 2, tau acts on points as the Frobenius map
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TauElement {
+    r0: SignedLongInt,
+    r1: SignedLongInt,
+}
+
+impl TauElement {
+    fn from_i64(r0: i64, r1: i64) -> Self {
+        Self { r0: SignedLongInt::from_i64(r0), r1: SignedLongInt::from_i64(r1) }
+    }
+
+    fn from_scalar(k: &UnsignedLongInt) -> Self {
+        Self { r0: SignedLongInt::new(false, k.clone()), r1: SignedLongInt::zero() }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.r0.is_zero() && self.r1.is_zero()
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Self { r0: self.r0.add(&other.r0), r1: self.r1.add(&other.r1) }
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Self { r0: self.r0.sub(&other.r0), r1: self.r1.sub(&other.r1) }
+    }
+
+    // (a0 + a1 tau)(b0 + b1 tau) = a0 b0 - 2 a1 b1 + (a0 b1 + a1 b0 + mu a1 b1) tau
+    fn mul(&self, other: &Self, mu: i64) -> Self {
+        let a1b1 = self.r1.mul(&other.r1);
+        Self {
+            r0: self.r0.mul(&other.r0).sub(&a1b1.mul_i64(2)),
+            r1: self.r0.mul(&other.r1).add(&self.r1.mul(&other.r0)).add(&a1b1.mul_i64(mu)),
+        }
+    }
+
+    // tau (a0 + a1 tau) = -2 a1 + (a0 + mu a1) tau
+    fn mul_tau(&self, mu: i64) -> Self {
+        Self { r0: self.r1.mul_i64(-2), r1: self.r0.add(&self.r1.mul_i64(mu)) }
+    }
+
+    // (r0 + r1 tau) / tau for even r0
+    fn div_tau(&self, mu: i64) -> Self {
+        let half = self.r0.div_floor(&UnsignedLongInt::from(2));
+        Self { r0: self.r1.add(&half.mul_i64(mu)), r1: half.neg() }
+    }
+
+    // N(a0 + a1 tau) = a0^2 + mu a0 a1 + 2 a1^2
+    fn norm(&self, mu: i64) -> UnsignedLongInt {
+        let n = self.r0.mul(&self.r0)
+            .add(&self.r0.mul(&self.r1).mul_i64(mu))
+            .add(&self.r1.mul(&self.r1).mul_i64(2));
+        n.magnitude().clone()
+    }
+
+    // k - q d with q = round(k / d) in Z[tau], the remainder has norm below N(d)
+    fn rem(&self, d: &Self, mu: i64) -> Self {
+        // k / d = k conj(d) / N(d), conj(d0 + d1 tau) = d0 + mu d1 - d1 tau
+        let n = d.norm(mu);
+        let conj = Self { r0: d.r0.add(&d.r1.mul_i64(mu)), r1: d.r1.neg() };
+        let lambda = self.mul(&conj, mu);
+
+        let q = round(&lambda.r0, &lambda.r1, &n, mu);
+        self.sub(&q.mul(d, mu))
+    }
+}
+
+// Nearest element of Z[tau] to (a0 + a1 tau) / n, Solinas' rounding
+fn round(a0: &SignedLongInt, a1: &SignedLongInt, n: &UnsignedLongInt, mu: i64) -> TauElement {
+    let n_signed = SignedLongInt::new(false, n.clone());
+    let two_n = n.add(n);
+    // f = floor((2a + n) / 2n), e = a - f n so that eta = e / n
+    let f0 = a0.mul_i64(2).add(&n_signed).div_floor(&two_n);
+    let f1 = a1.mul_i64(2).add(&n_signed).div_floor(&two_n);
+    let e0 = a0.sub(&f0.mul(&n_signed));
+    let e1 = a1.sub(&f1.mul(&n_signed));
+
+    let eta = e0.mul_i64(2).add(&e1.mul_i64(mu));
+    let minus3 = e0.sub(&e1.mul_i64(3 * mu));
+    let plus4 = e0.add(&e1.mul_i64(4 * mu));
+    let bound = |k: i64| n_signed.mul_i64(k);
+
+    let (mut h0, mut h1) = (0, 0);
+    if !eta.less_than(&bound(1)) {
+        if minus3.less_than(&bound(-1)) { h1 = mu } else { h0 = 1 }
+    } else if !plus4.less_than(&bound(2)) {
+        h1 = mu;
+    }
+    if eta.less_than(&bound(-1)) {
+        if !minus3.less_than(&bound(1)) { h1 = -mu } else { h0 = -1 }
+    } else if plus4.less_than(&bound(-2)) {
+        h1 = -mu;
+    }
+
+    TauElement { r0: f0.add(&SignedLongInt::from_i64(h0)), r1: f1.add(&SignedLongInt::from_i64(h1)) }
+}
+
+// tau^k from tau^(i + 1) = tau tau^i
+fn tau_pow(k: usize, mu: i64) -> TauElement {
+    let mut out = TauElement::from_i64(1, 0);
+    for _ in 0..k {
+        out = out.mul_tau(mu);
+    }
+    out
+}
+
+// #E(GF(2^m)) of the Koblitz curve with mu = (-1)^(1 - a), tau^m = r0 + r1 tau gives V_m = 2 r0 + mu r1
+pub(crate) fn koblitz_order(m: usize, mu: i64) -> UnsignedLongInt {
+    let t = tau_pow(m, mu);
+    let v = t.r0.mul_i64(2).add(&t.r1.mul_i64(mu));
+
+    let q_plus_1 = UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(m as u64)).add(&UnsignedLongInt::from(1));
+    SignedLongInt::new(false, q_plus_1).sub(&v).magnitude().clone()
+}
+
+// (tau^m - 1) / (tau - 1) = 1 + tau + ... + tau^(m - 1)
+fn delta(mu: i64) -> TauElement {
+    let mut power = TauElement::from_i64(1, 0);
+    let mut sum = TauElement::from_i64(0, 0);
+    for _ in 0..MOD_DEG {
+        sum = sum.add(&power);
+        power = power.mul_tau(mu);
+    }
+    sum
+}
+
+impl Curve {
+    /// a in {0, 1} and b = 1
+    pub fn is_koblitz(&self) -> bool {
+        self.b() == &GF2Element::ONE && (self.a() == &GF2Element::ZERO || self.a() == &GF2Element::ONE)
+    }
+
+    /// (x, y) -> (x^2, y^2), an endomorphism of Koblitz curves
+    pub fn frobenius_point(&self, p: &Point) -> Point {
+        match p {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x, y } => Point::Affine { x: x.sqr(), y: y.sqr() },
+        }
+    }
+
+    /// tau-adic NAF of k reduced modulo (tau^m - 1) / (tau - 1), least significant digit first
+    pub fn tnaf(&self, k: &UnsignedLongInt) -> Result<Vec<i32>, CurveError> {
+        let mu = self.mu()?;
+        let mut r = TauElement::from_scalar(k).rem(&delta(mu), mu);
+
+        let mut digits = Vec::with_capacity(MOD_DEG + 2);
+        while !r.is_zero() {
+            let mut u = 0;
+            if r.r0.low_bits(1) == 1 {
+                // u = 2 - ((r0 - 2 r1) mod 4)
+                u = 2 - r.r0.sub(&r.r1.mul_i64(2)).low_bits(2) as i32;
+                r.r0 = r.r0.sub(&SignedLongInt::from_i64(u as i64));
+            }
+            digits.push(u);
+            r = r.div_tau(mu);
+        }
+        Ok(digits)
+    }
+
+    /// Width-w tau-adic NAF of k reduced modulo (tau^m - 1) / (tau - 1). A non-zero digit u
+    /// stands for the representative of u modulo tau^w returned by `tnaf_w_mul`'s table.
+    pub fn tnaf_w(&self, k: &UnsignedLongInt, w: usize) -> Result<Vec<i32>, CurveError> {
+        let mu = self.mu()?;
+        let (t_w, alphas) = tnaf_w_parameters(w, mu);
+        let mut r = TauElement::from_scalar(k).rem(&delta(mu), mu);
+
+        let mut digits = Vec::with_capacity(MOD_DEG + 2);
+        while !r.is_zero() {
+            let mut u = 0;
+            if r.r0.low_bits(1) == 1 {
+                // u = (r0 + r1 t_w) mods 2^w
+                let v = r.r0.add(&r.r1.mul_i64(t_w)).low_bits(w) as i64;
+                u = if v >= 1 << (w - 1) { v - (1 << w) } else { v };
+                let alpha = &alphas[(u.unsigned_abs() as usize - 1) / 2];
+                r = if u > 0 { r.sub(alpha) } else { r.add(alpha) };
+            }
+            digits.push(u as i32);
+            r = r.div_tau(mu);
+        }
+        Ok(digits)
+    }
+
+    /// kP with the tau-adic NAF, Frobenius maps instead of doublings. P must lie in a subgroup of order
+    /// coprime to #E(GF(2)), e.g. the prime order subgroup of a standard Koblitz curve.
+    pub fn tnaf_mul(&self, p: &Point, k: &UnsignedLongInt) -> Result<Point, CurveError> {
+        let digits = self.tnaf(k)?;
+        let neg = self.neg(p);
+
+        let mut out = LdPoint::infinity();
+        for d in digits.iter().rev() {
+            out = ld_frobenius(&out);
+            if *d == 1 {
+                out = self.ld_add_mixed(&out, p);
+            } else if *d == -1 {
+                out = self.ld_add_mixed(&out, &neg);
+            }
+        }
+        Ok(self.to_affine(&out))
+    }
+
+    /// kP with the width-w tau-adic NAF and 2^(w-2) precomputed points, same restriction on P as `tnaf_mul`
+    pub fn tnaf_w_mul(&self, p: &Point, k: &UnsignedLongInt, w: usize) -> Result<Point, CurveError> {
+        let mu = self.mu()?;
+        let digits = self.tnaf_w(k, w)?;
+        let (_, alphas) = tnaf_w_parameters(w, mu);
+
+        // alpha_u P = beta P + gamma tau(P)
+        let p_tau = self.frobenius_point(p);
+        let table: Vec<_> = alphas.iter().map(|alpha| {
+            let beta = alpha.r0.to_i64().expect("small representative");
+            let gamma = alpha.r1.to_i64().expect("small representative");
+            self.ld_add(&self.small_multiple(p, beta), &self.small_multiple(&p_tau, gamma))
+        }).collect();
+        let table = self.to_affine_batch(&table);
+
+        let mut out = LdPoint::infinity();
+        for d in digits.iter().rev() {
+            out = ld_frobenius(&out);
+            if *d > 0 {
+                out = self.ld_add_mixed(&out, &table[(*d as usize - 1) / 2]);
+            } else if *d < 0 {
+                out = self.ld_add_mixed(&out, &self.neg(&table[(d.unsigned_abs() as usize - 1) / 2]));
+            }
+        }
+        Ok(self.to_affine(&out))
+    }
+
+    /// #E(GF(2^m)) = 2^m + 1 - V_m, V_m = tau^m + conj(tau)^m is the trace of the Frobenius map
+    pub fn koblitz_group_order(&self) -> Result<UnsignedLongInt, CurveError> {
+        Ok(koblitz_order(MOD_DEG, self.mu()?))
+    }
+
+    // mu = (-1)^(1 - a)
+    fn mu(&self) -> Result<i64, CurveError> {
+        if !self.is_koblitz() {
+            return Err(CurveError::NotKoblitz);
+        }
+        Ok(if self.a() == &GF2Element::ONE { 1 } else { -1 })
+    }
+
+    fn small_multiple(&self, p: &Point, k: i64) -> LdPoint {
+        let base = if k < 0 { self.neg(p) } else { p.clone() };
+        let k = k.unsigned_abs();
+
+        let mut out = LdPoint::infinity();
+        for i in (0..64 - k.leading_zeros()).rev() {
+            out = self.ld_double(&out);
+            if k >> i & 1 == 1 {
+                out = self.ld_add_mixed(&out, &base);
+            }
+        }
+        out
+    }
+}
+
+// (X : Y : Z) -> (X^2 : Y^2 : Z^2)
+fn ld_frobenius(p: &LdPoint) -> LdPoint {
+    LdPoint { x: p.x.sqr(), y: p.y.sqr(), z: p.z.sqr() }
+}
+
+// t_w = 2 U_(w-1) / U_w mod 2^w, the image of tau in Z[tau] / (tau^w) = Z / 2^w,
+// and the representatives alpha_u = u mod tau^w for odd u < 2^(w-1)
+fn tnaf_w_parameters(w: usize, mu: i64) -> (i64, Vec<TauElement>) {
+    assert!((2..=16).contains(&w), "window width must be between 2 and 16");
+    let modulus = 1i64 << w;
+
+    // Lucas sequence U_0 = 0, U_1 = 1, U_(k+1) = mu U_k - 2 U_(k-1)
+    let (mut u_prev, mut u) = (0i64, 1i64);
+    for _ in 1..w {
+        (u_prev, u) = (u, mu * u - 2 * u_prev);
+    }
+    let u_inv = (1..modulus).step_by(2).find(|v| (u * v).rem_euclid(modulus) == 1).expect("U_w is odd");
+    let t_w = (2 * u_prev * u_inv).rem_euclid(modulus);
+
+    let tau_w = tau_pow(w, mu);
+    let alphas = (1..modulus / 2).step_by(2)
+        .map(|u| TauElement::from_i64(u, 0).rem(&tau_w, mu))
+        .collect();
+    (t_w, alphas)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::curve::tests::{point_after, test_curve};
+    use crate::DIM;
+    use super::*;
+
+    fn koblitz_curve(a: GF2Element<DIM>) -> Result<(Curve, Point), Box<dyn Error>> {
+        let curve = Curve::new(a, GF2Element::ONE)?;
+        let q = point_after(&curve, 2);
+        // tau(Q) - Q is killed by (tau^m - 1) / (tau - 1)
+        let p = curve.add(&curve.frobenius_point(&q), &curve.neg(&q));
+        Ok((curve, p))
+    }
+
+    fn evaluate(digits: &[i32], mu: i64) -> TauElement {
+        digits.iter().rev().fold(TauElement::from_i64(0, 0), |acc, d| {
+            let acc = acc.mul_tau(mu);
+            TauElement { r0: acc.r0.add(&SignedLongInt::from_i64(*d as i64)), r1: acc.r1 }
+        })
+    }
+
+    #[test]
+    fn tnaf_test() -> Result<(), Box<dyn Error>> {
+        let (curve, _) = koblitz_curve(GF2Element::ONE)?;
+        let k = UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(MOD_DEG as u64 + 5)).add(&UnsignedLongInt::from(rand::random::<u64>()));
+        let mu = 1;
+
+        let digits = curve.tnaf(&k)?;
+        assert!(digits.len() <= MOD_DEG + 2);
+        assert!(digits.windows(2).all(|d| d[0] == 0 || d[1] == 0));
+
+        // the expansion is congruent to k modulo delta
+        let diff = TauElement::from_scalar(&k).sub(&evaluate(&digits, mu));
+        assert!(diff.rem(&delta(mu), mu).is_zero());
+
+        // small scalars are already reduced
+        assert_eq!(evaluate(&curve.tnaf(&UnsignedLongInt::from(7))?, mu), TauElement::from_i64(7, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tnaf_mul_test() -> Result<(), Box<dyn Error>> {
+        for a in [GF2Element::ZERO, GF2Element::ONE] {
+            let (curve, p) = koblitz_curve(a)?;
+            let k = UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(MOD_DEG as u64)).sub(&UnsignedLongInt::from(rand::random::<u64>()));
+
+            let expected = curve.ld_mul(&p, &k);
+            assert_eq!(curve.tnaf_mul(&p, &k)?, expected);
+            assert_eq!(curve.tnaf_w_mul(&p, &k, 4)?, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn tnaf_w_test() -> Result<(), Box<dyn Error>> {
+        let (curve, p) = koblitz_curve(GF2Element::ZERO)?;
+        let k = UnsignedLongInt::from(rand::random::<u64>());
+
+        for w in [2, 3, 5] {
+            let digits = curve.tnaf_w(&k, w)?;
+            for (i, d) in digits.iter().enumerate().filter(|(_, d)| **d != 0) {
+                assert_eq!(d.rem_euclid(2), 1);
+                assert!(digits[i + 1..].iter().take(w - 1).all(|d| *d == 0));
+            }
+        }
+        assert_eq!(curve.tnaf_w_mul(&p, &k, 5)?, curve.tnaf_mul(&p, &k)?);
+
+        let (t_w, alphas) = tnaf_w_parameters(4, -1);
+        // t_4 is a root of x^2 - mu x + 2 modulo 16
+        assert_eq!((t_w * t_w + t_w + 2) % 16, 0);
+        assert_eq!(alphas.len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn group_order_test() -> Result<(), Box<dyn Error>> {
+        for (a, cofactor) in [(GF2Element::ZERO, 4), (GF2Element::ONE, 2)] {
+            let curve = Curve::new(a, GF2Element::ONE)?;
+            let order = curve.koblitz_group_order()?;
+
+            // #E(GF(2)) divides #E(GF(2^m))
+            assert!((0..cofactor / 2).all(|i| !order.get_bit(i)));
+            assert_eq!(curve.ld_mul(&point_after(&curve, 2), &order), Point::Infinity);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn not_koblitz_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        assert_eq!(curve.tnaf(&UnsignedLongInt::from(5)), Err(CurveError::NotKoblitz));
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use vl_big_ints::UnsignedLongInt;
+use crate::curve::{Curve, Point};
+use crate::{GF2Element, DIM, MOD_DEG};
+
+// Scalars up to this many bits take the same number of ladder steps
+pub(crate) const LADDER_BITS: usize = MOD_DEG + 1;
+
+impl Curve {
+    /// x(kP) with the López-Dahab Montgomery ladder, None for the point at infinity.
+    /// Every scalar below 2^(m+1) runs the same sequence of field operations.
+    pub fn ladder_x(&self, x: &GF2Element<DIM>, k: &UnsignedLongInt) -> Option<GF2Element<DIM>> {
+        self.ladder_x_with_z(x, k, &GF2Element::ONE)
+    }
+
+    /// `ladder_x` starting from P = (x z : z), any non-zero z gives the same result
+    /// while the intermediate values change with it
+    pub fn ladder_x_with_z(&self, x: &GF2Element<DIM>, k: &UnsignedLongInt, z: &GF2Element<DIM>) -> Option<GF2Element<DIM>> {
+        let ((x1, z1), _) = self.ladder(x, k, z);
+        if z1 == GF2Element::ZERO {
+            return None;
+        }
+        Some(x1.mul(&z1.inverse()))
+    }
+
+    /// kP with the x-only ladder and the y-coordinate recovered at the end
+    pub fn ladder_mul(&self, p: &Point, k: &UnsignedLongInt) -> Point {
+        self.ladder_mul_with_z(p, k, &GF2Element::ONE)
+    }
+
+    /// `ladder_mul` with the ladder started from (x z : z) as in `ladder_x_with_z`
+    pub fn ladder_mul_with_z(&self, p: &Point, k: &UnsignedLongInt, z: &GF2Element<DIM>) -> Point {
+        let (x, y) = match p {
+            Point::Infinity => return Point::Infinity,
+            Point::Affine { x, y } => (x, y),
+        };
+        if x == &GF2Element::ZERO {
+            // point of order 2
+            return if k.get_bit(0) { p.clone() } else { Point::Infinity };
+        }
+
+        let ((x1, z1), (x2, z2)) = self.ladder(x, k, z);
+        if z1 == GF2Element::ZERO {
+            return Point::Infinity;
+        }
+        if z2 == GF2Element::ZERO {
+            // (k + 1) P = O, so kP = -P
+            return self.neg(p);
+        }
+
+        // x1 = X1 / Z1, x2 = X2 / Z2,
+        // y(kP) = (x + x1) ((x1 + x)(x2 + x) + x^2 + y) / x + y with one inversion of x Z1 Z2
+        let z1z2 = z1.mul(&z2);
+        let inv = x.mul(&z1z2).inverse();
+        let x_k = x1.mul(&x.mul(&z2)).mul(&inv);
+        let t = x1.add(&x.mul(&z1)).mul(&x2.add(&x.mul(&z2))).add(&x.sqr().add(y).mul(&z1z2));
+        let y_k = x.add(&x_k).mul(&t).mul(&inv).add(y);
+
+        Point::Affine { x: x_k, y: y_k }
+    }
+
+    // Projective x-coordinates of (kP, (k + 1)P), starting from (O, P) = ((1 : 0), (x z : z)).
+    // The invariant R1 - R0 = P lets the addition use x alone.
+    fn ladder(&self, x: &GF2Element<DIM>, k: &UnsignedLongInt, z: &GF2Element<DIM>) -> (XzPair, XzPair) {
+        assert!(z != &GF2Element::ZERO, "projective Z must be non-zero");
+        let mut r0 = (GF2Element::ONE, GF2Element::ZERO);
+        let mut r1 = (x.mul(z), z.clone());
+
+        let bits = k.get_highest_set_bit().map_or(0, |top| top + 1).max(LADDER_BITS);
+        for i in (0..bits).rev() {
+            let bit = k.get_bit(i);
+            conditional_swap(&mut r0, &mut r1, bit);
+            r1 = madd(x, &r0, &r1);
+            r0 = self.mdouble(&r0);
+            conditional_swap(&mut r0, &mut r1, bit);
+        }
+
+        (r0, r1)
+    }
+
+    // 2 (X : Z) = (X^4 + b Z^4 : X^2 Z^2)
+    fn mdouble(&self, (x, z): &XzPair) -> XzPair {
+        let x2 = x.sqr();
+        let z2 = z.sqr();
+        (x2.sqr().add(&self.b().mul(&z2.sqr())), x2.mul(&z2))
+    }
+}
+
+type XzPair = (GF2Element<DIM>, GF2Element<DIM>);
+
+// (X1 : Z1) + (X2 : Z2) knowing their difference has x-coordinate x:
+// Z = (X1 Z2 + X2 Z1)^2, X = x Z + X1 Z2 X2 Z1
+fn madd(x: &GF2Element<DIM>, (x1, z1): &XzPair, (x2, z2): &XzPair) -> XzPair {
+    let a = x1.mul(z2);
+    let b = x2.mul(z1);
+    let z = a.add(&b).sqr();
+    (x.mul(&z).add(&a.mul(&b)), z)
+}
+
+fn conditional_swap(p: &mut XzPair, q: &mut XzPair, swap: bool) {
+    conditional_swap_elements(&mut p.0, &mut q.0, swap);
+    conditional_swap_elements(&mut p.1, &mut q.1, swap);
+}
+
+// Swaps through a mask instead of a branch on the scalar bit
+pub(crate) fn conditional_swap_elements(a: &mut GF2Element<DIM>, b: &mut GF2Element<DIM>, swap: bool) {
+    let mask = (swap as usize).wrapping_neg();
+    for i in 0..DIM {
+        let t = mask & (a.data[i] ^ b.data[i]);
+        a.data[i] ^= t;
+        b.data[i] ^= t;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::curve::tests::{point_after, test_curve};
+    use super::*;
+
+    #[test]
+    fn ladder_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let p = point_after(&curve, 2);
+
+        for k in [0, 1, 2, rand::random::<u64>()] {
+            let k = UnsignedLongInt::from(k);
+            let expected = curve.ld_mul(&p, &k);
+            assert_eq!(curve.ladder_mul(&p, &k), expected);
+            assert_eq!(curve.ladder_x(p.x().unwrap(), &k).as_ref(), expected.x());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn order_two_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let t = curve.point(GF2Element::ZERO, curve.b().frobenius(MOD_DEG - 1))?;
+
+        assert_eq!(curve.ladder_mul(&t, &UnsignedLongInt::from(3)), t);
+        assert_eq!(curve.ladder_x(&GF2Element::ZERO, &UnsignedLongInt::from(2)), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn conditional_swap_test() {
+        let mut p = (GF2Element::ONE, GF2Element::ZERO);
+        let mut q = (GF2Element::ZERO, GF2Element::ONE);
+
+        conditional_swap(&mut p, &mut q, false);
+        assert_eq!(p, (GF2Element::ONE, GF2Element::ZERO));
+        conditional_swap(&mut p, &mut q, true);
+        assert_eq!(p, (GF2Element::ZERO, GF2Element::ONE));
+        assert_eq!(q, (GF2Element::ONE, GF2Element::ZERO));
+    }
+}
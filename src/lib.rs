@@ -9,7 +9,7 @@ const MOD_DIM: usize = 7;
 pub const MOD: [usize; MOD_DIM] = [0x204003, 0, 0, 0, 0, 0, 0x800000000];
 pub const MOD_DEG: usize = 419;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub struct GF2Element<const DIM: usize> {
     // TODO make private
     pub data: [usize; DIM],
@@ -59,7 +59,7 @@ impl GF2Element<DIM> {
 
     #[inline]
     pub fn get_coef(&self, n: usize) -> bool {
-        if self.data[n / DIM] & (1 << n % usize::BITS as usize) != 0 { true } else { false }
+        if self.data[n / usize::BITS as usize] & (1 << n % usize::BITS as usize) != 0 { true } else { false }
     }
 
     pub fn deg(&self) -> usize {
@@ -86,9 +86,17 @@ impl GF2Element<DIM> {
     }
 
 
-    // TODO improve performance
     pub fn sqr(&self) -> Self {
-        self.mul(self)
+        // squaring is linear in characteristic 2: bit 2i of the square is bit i of the element
+        let mut temp_array = [0; DIM * 2];
+
+        for i in 0..DIM * usize::BITS as usize {
+            if self.data[i / usize::BITS as usize] & (1 << (i % usize::BITS as usize)) != 0 {
+                temp_array[2 * i / usize::BITS as usize] |= 1 << (2 * i % usize::BITS as usize);
+            }
+        }
+
+        Self { data: modulo(temp_array) }
     }
 
     // TODO improve performance
@@ -196,7 +204,39 @@ fn deg(arr: &[usize]) -> usize {
 
 mod display;
 mod from_str;
+mod long_int;
 mod solve_sq_eq;
+pub mod additive_fft;
+pub mod agm;
+pub mod bit_matrix;
+pub mod curve;
+pub mod curve_gen;
+pub mod dlog;
+pub mod domain;
+pub mod dstu4145;
+pub mod ecdh;
+pub mod edwards;
+pub mod encoding;
+pub mod extension;
+pub mod field;
+pub mod frobenius;
+pub mod gf2m;
+pub mod hardened;
+pub mod hash_to_curve;
+pub mod hecc;
+pub mod koblitz;
+pub mod ladder;
+pub mod lopez_dahab;
+pub mod matrix;
+pub mod min_poly;
+pub mod msm;
+pub mod order;
+pub mod pairing;
+pub mod poly;
+pub mod scalar_mul;
+pub mod small_field;
+pub mod standard_curves;
+pub mod validation;
 
 #[cfg(test)]
 mod tests {
@@ -286,4 +326,15 @@ mod tests {
         assert_eq!(GF2Element::ONE, d.pow(&(UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(M as u64)).sub(&UnsignedLongInt::from(1)))));
         Ok(())
     }
+
+    #[test]
+    fn get_coef_test() -> Result<(), Box<dyn Error>> {
+        // x^n has the single coefficient n, including the ones past the first word
+        let x = GF2Element::from_str("2")?;
+        for n in [0, 1, 63, 64, 65, 200, 418] {
+            let p = x.pow(&UnsignedLongInt::from(n as u64));
+            assert!((0..MOD_DEG).all(|i| p.get_coef(i) == (i == n)));
+        }
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,325 @@
+use rand::RngCore;
+use vl_big_ints::UnsignedLongInt;
+
+// Integer helpers on top of vl_big_ints that the field code needs
+
+pub(crate) fn is_zero(a: &UnsignedLongInt) -> bool {
+    a.get_highest_set_bit().is_none()
+}
+
+/// Schoolbook binary long division, returns (a / b, a % b)
+pub(crate) fn div_rem(a: &UnsignedLongInt, b: &UnsignedLongInt) -> (UnsignedLongInt, UnsignedLongInt) {
+    assert!(!is_zero(b), "division by zero");
+
+    let one = UnsignedLongInt::from(1);
+    let mut q = UnsignedLongInt::from(0);
+    let mut r = UnsignedLongInt::from(0);
+    let Some(top) = a.get_highest_set_bit() else {
+        return (q, r);
+    };
+
+    for i in (0..top + 1).rev() {
+        r = r.add(&r);
+        if a.get_bit(i) {
+            r = r.add(&one);
+        }
+        q = q.add(&q);
+        if &r >= b {
+            r = r.sub(b);
+            q = q.add(&one);
+        }
+    }
+
+    (q, r)
+}
+
+pub(crate) fn to_u64(a: &UnsignedLongInt) -> Option<u64> {
+    match a.get_highest_set_bit() {
+        None => Some(0),
+        Some(top) if top < 64 => Some((0..top + 1).filter(|&i| a.get_bit(i)).fold(0, |acc, i| acc | 1 << i)),
+        _ => None,
+    }
+}
+
+/// Number from 64-bit words, the most significant first
+pub(crate) fn from_words(words: &[u64]) -> UnsignedLongInt {
+    let base = UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(64));
+    words.iter().fold(UnsignedLongInt::from(0), |acc, w| acc.mul(&base).add(&UnsignedLongInt::from(*w)))
+}
+
+/// Uniform integer in [1, n - 1] by rejection sampling on bit_length(n) random bits
+pub(crate) fn random_below<R: RngCore>(rng: &mut R, n: &UnsignedLongInt) -> UnsignedLongInt {
+    let bits = n.get_highest_set_bit().map_or(0, |top| top + 1);
+    assert!(bits > 1, "range must contain a non-zero value");
+
+    let words = bits.div_ceil(64);
+    loop {
+        let mut random: Vec<u64> = (0..words).map(|_| rng.next_u64()).collect();
+        let top_bits = bits % 64;
+        if top_bits > 0 {
+            random[0] &= (1 << top_bits) - 1;
+        }
+
+        let k = from_words(&random);
+        if !is_zero(&k) && &k < n {
+            return k;
+        }
+    }
+}
+
+pub(crate) fn rem(a: &UnsignedLongInt, n: &UnsignedLongInt) -> UnsignedLongInt {
+    div_rem(a, n).1
+}
+
+/// (a + b) mod n for a, b < n
+pub(crate) fn mod_add(a: &UnsignedLongInt, b: &UnsignedLongInt, n: &UnsignedLongInt) -> UnsignedLongInt {
+    let s = a.add(b);
+    if &s >= n { s.sub(n) } else { s }
+}
+
+/// (a - b) mod n for a, b < n
+pub(crate) fn mod_sub(a: &UnsignedLongInt, b: &UnsignedLongInt, n: &UnsignedLongInt) -> UnsignedLongInt {
+    if a >= b { a.sub(b) } else { a.add(n).sub(b) }
+}
+
+/// a * b mod n with double-and-add, so intermediate values stay below 2n
+pub(crate) fn mod_mul(a: &UnsignedLongInt, b: &UnsignedLongInt, n: &UnsignedLongInt) -> UnsignedLongInt {
+    let a = rem(a, n);
+    let mut r = UnsignedLongInt::from(0);
+    let Some(top) = b.get_highest_set_bit() else {
+        return r;
+    };
+
+    for i in (0..top + 1).rev() {
+        r = mod_add(&r, &r, n);
+        if b.get_bit(i) {
+            r = mod_add(&r, &a, n);
+        }
+    }
+
+    r
+}
+
+pub(crate) fn mod_pow(a: &UnsignedLongInt, e: &UnsignedLongInt, n: &UnsignedLongInt) -> UnsignedLongInt {
+    let mut r = rem(&UnsignedLongInt::from(1), n);
+    let Some(top) = e.get_highest_set_bit() else {
+        return r;
+    };
+
+    for i in (0..top + 1).rev() {
+        r = mod_mul(&r, &r, n);
+        if e.get_bit(i) {
+            r = mod_mul(&r, a, n);
+        }
+    }
+
+    r
+}
+
+/// Miller-Rabin test with the first `rounds` primes as bases, so the answer depends on n only
+/// and anyone repeating it gets the same result
+pub(crate) fn is_probable_prime(n: &UnsignedLongInt, rounds: usize) -> bool {
+    let bases = (2u64..).filter(|&p| (2..p).take_while(|d| d * d <= p).all(|d| p % d != 0)).take(rounds);
+    miller_rabin(n, bases.map(|p| rem(&UnsignedLongInt::from(p), n)))
+}
+
+// n - 1 = d 2^s with d odd, every base a must pass a^d = 1 or a^(d 2^i) = -1 for some i < s.
+// Bases 0, 1 and n - 1 say nothing and are skipped.
+fn miller_rabin(n: &UnsignedLongInt, bases: impl Iterator<Item = UnsignedLongInt>) -> bool {
+    let one = UnsignedLongInt::from(1);
+    let three = UnsignedLongInt::from(3);
+    if n <= &three {
+        return n > &one;
+    }
+    if !n.get_bit(0) {
+        return false;
+    }
+
+    let n_minus_one = n.sub(&one);
+    let s = (0..).find(|&i| n_minus_one.get_bit(i)).expect("n - 1 is positive");
+    let (d, _) = div_rem(&n_minus_one, &UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(s as u64)));
+
+    bases.filter(|a| a > &one && a < &n_minus_one).all(|a| {
+        let mut x = mod_pow(&a, &d, n);
+        if x == one || x == n_minus_one {
+            return true;
+        }
+        for _ in 1..s {
+            x = mod_mul(&x, &x, n);
+            if x == n_minus_one {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// Sign and magnitude on top of `UnsignedLongInt`, zero is never negative
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SignedLongInt {
+    negative: bool,
+    magnitude: UnsignedLongInt,
+}
+
+impl SignedLongInt {
+    pub(crate) fn new(negative: bool, magnitude: UnsignedLongInt) -> Self {
+        let negative = negative && !is_zero(&magnitude);
+        Self { negative, magnitude }
+    }
+
+    pub(crate) fn from_i64(v: i64) -> Self {
+        Self::new(v < 0, UnsignedLongInt::from(v.unsigned_abs()))
+    }
+
+    pub(crate) fn zero() -> Self {
+        Self::from_i64(0)
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        is_zero(&self.magnitude)
+    }
+
+    pub(crate) fn magnitude(&self) -> &UnsignedLongInt {
+        &self.magnitude
+    }
+
+    /// Value as i64 if it fits
+    pub(crate) fn to_i64(&self) -> Option<i64> {
+        let m = i64::try_from(to_u64(&self.magnitude)?).ok()?;
+        Some(if self.negative { -m } else { m })
+    }
+
+    pub(crate) fn neg(&self) -> Self {
+        Self::new(!self.negative, self.magnitude.clone())
+    }
+
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        if self.negative == other.negative {
+            return Self::new(self.negative, self.magnitude.add(&other.magnitude));
+        }
+        if self.magnitude >= other.magnitude {
+            Self::new(self.negative, self.magnitude.sub(&other.magnitude))
+        } else {
+            Self::new(other.negative, other.magnitude.sub(&self.magnitude))
+        }
+    }
+
+    pub(crate) fn sub(&self, other: &Self) -> Self {
+        self.add(&other.neg())
+    }
+
+    pub(crate) fn mul(&self, other: &Self) -> Self {
+        Self::new(self.negative != other.negative, self.magnitude.mul(&other.magnitude))
+    }
+
+    pub(crate) fn mul_i64(&self, v: i64) -> Self {
+        self.mul(&Self::from_i64(v))
+    }
+
+    /// Non-negative residue modulo 2^bits, bits < 64
+    pub(crate) fn low_bits(&self, bits: usize) -> u64 {
+        let r = (0..bits).filter(|&i| self.magnitude.get_bit(i)).fold(0u64, |acc, i| acc | 1 << i);
+        if self.negative { r.wrapping_neg() & ((1 << bits) - 1) } else { r }
+    }
+
+    /// floor(self / d) for d > 0
+    pub(crate) fn div_floor(&self, d: &UnsignedLongInt) -> Self {
+        let (q, r) = div_rem(&self.magnitude, d);
+        if !self.negative {
+            Self::new(false, q)
+        } else if is_zero(&r) {
+            Self::new(true, q)
+        } else {
+            Self::new(true, q.add(&UnsignedLongInt::from(1)))
+        }
+    }
+
+    pub(crate) fn less_than(&self, other: &Self) -> bool {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude < other.magnitude,
+            (true, true) => self.magnitude > other.magnitude,
+            (negative, _) => negative,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::str::FromStr;
+    use super::*;
+
+    #[test]
+    fn div_rem_test() -> Result<(), Box<dyn Error>> {
+        let a = UnsignedLongInt::from_str("044B060AED749F4637B2151CB79A40D8CF329AFCB99B19054104E5098AC91FF5C34512510BAEAEC154C1C5ABD4571873F802C5A4BF")?;
+        let b = UnsignedLongInt::from_str("0314838BB0E599D370485AC3DBA721D9F32F60FA2FAC8F8BB49A52199A8A7745")?;
+
+        let (q, r) = div_rem(&a, &b);
+        assert!(r < b);
+        assert_eq!(q.mul(&b).add(&r), a);
+
+        Ok(())
+    }
+
+    #[test]
+    fn mod_arithmetic_test() {
+        let n = UnsignedLongInt::from(1_000_000_007);
+        let a = UnsignedLongInt::from(123_456_789);
+        let b = UnsignedLongInt::from(987_654_321);
+
+        assert_eq!(to_u64(&mod_mul(&a, &b, &n)), Some(123_456_789 * 987_654_321 % 1_000_000_007));
+        assert_eq!(to_u64(&mod_sub(&a, &b, &n)), Some(1_000_000_007 + 123_456_789 - 987_654_321));
+        // Fermat's little theorem
+        assert_eq!(mod_pow(&a, &n.sub(&UnsignedLongInt::from(1)), &n), UnsignedLongInt::from(1));
+        assert_eq!(to_u64(&UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(64))), None);
+    }
+
+    #[test]
+    fn random_test() {
+        let n = UnsignedLongInt::from_str("0314838BB0E599D370485AC3DBA721D9F32F60FA2FAC8F8BB49A52199A8A7745").unwrap();
+        for _ in 0..20 {
+            let k = random_below(&mut rand::thread_rng(), &n);
+            assert!(!is_zero(&k) && k < n);
+        }
+
+        assert_eq!(from_words(&[1, 0]), UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(64)));
+        assert_eq!(random_below(&mut rand::thread_rng(), &UnsignedLongInt::from(2)), UnsignedLongInt::from(1));
+    }
+
+    #[test]
+    fn primality_test() -> Result<(), Box<dyn Error>> {
+        // the bases 2, 3, 5, ... reduced mod n, also for n below them
+        for p in [2, 3, 5, 7, 11, 1009, 1_000_000_007] {
+            assert!(is_probable_prime(&UnsignedLongInt::from(p), 20));
+        }
+        // 561 is a Carmichael number
+        for c in [0, 1, 4, 9, 561, 1_000_000_007 * 3] {
+            assert!(!is_probable_prime(&UnsignedLongInt::from(c), 20));
+        }
+        // order of the NIST K-163 base point
+        let n = UnsignedLongInt::from_str("4000000000000000000020108A2E0CC0D99F8A5EF")?;
+        assert!(is_probable_prime(&n, 20));
+        assert!(!is_probable_prime(&n.mul(&UnsignedLongInt::from(3)), 20));
+        // 3215031751 = 151 * 751 * 28351 is a strong pseudoprime to the bases 2, 3, 5 and 7
+        assert!(is_probable_prime(&UnsignedLongInt::from(3_215_031_751), 4));
+        assert!(!is_probable_prime(&UnsignedLongInt::from(3_215_031_751), 5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn signed_test() {
+        let a = SignedLongInt::from_i64(-7);
+        let b = SignedLongInt::from_i64(3);
+        let two = UnsignedLongInt::from(2);
+
+        assert_eq!(a.add(&b).to_i64(), Some(-4));
+        assert_eq!(b.sub(&a).to_i64(), Some(10));
+        assert_eq!(a.mul(&b).to_i64(), Some(-21));
+        assert_eq!(a.div_floor(&two).to_i64(), Some(-4));
+        assert_eq!(b.div_floor(&two).to_i64(), Some(1));
+        assert_eq!(a.low_bits(2), 1);
+        assert!(a.less_than(&b) && !b.less_than(&a));
+        assert_eq!(a.add(&a.neg()), SignedLongInt::zero());
+    }
+}
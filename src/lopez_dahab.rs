@@ -0,0 +1,220 @@
+use vl_big_ints::UnsignedLongInt;
+use crate::curve::{Curve, Point};
+use crate::field::{batch_inverse, BinaryField};
+use crate::{GF2Element, DIM};
+
+/// López-Dahab projective point (X : Y : Z) for the affine point (X / Z, Y / Z^2),
+/// Z = 0 is the point at infinity
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LdPoint<F = GF2Element<DIM>> {
+    pub x: F,
+    pub y: F,
+    pub z: F,
+}
+
+impl<F: BinaryField> LdPoint<F> {
+    /// (1 : 0 : 0)
+    pub fn infinity() -> Self {
+        Self { x: F::one(), y: F::zero(), z: F::zero() }
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.z.is_zero()
+    }
+}
+
+impl<F: BinaryField> From<&Point<F>> for LdPoint<F> {
+    fn from(p: &Point<F>) -> Self {
+        match p {
+            Point::Infinity => Self::infinity(),
+            Point::Affine { x, y } => Self { x: x.clone(), y: y.clone(), z: F::one() },
+        }
+    }
+}
+
+impl<F: BinaryField> Curve<F> {
+    /// Back to affine coordinates with a single inversion
+    pub fn to_affine(&self, p: &LdPoint<F>) -> Point<F> {
+        if p.is_infinity() {
+            return Point::Infinity;
+        }
+        let z_inv = p.z.inverse();
+        Point::Affine { x: p.x.mul(&z_inv), y: p.y.mul(&z_inv.sqr()) }
+    }
+
+    /// Converts many points with a single inversion shared through `batch_inverse`
+    pub fn to_affine_batch(&self, points: &[LdPoint<F>]) -> Vec<Point<F>> {
+        let finite: Vec<_> = points.iter().filter(|p| !p.is_infinity()).map(|p| p.z.clone()).collect();
+        let mut z_inv = batch_inverse(&finite).into_iter();
+
+        points.iter().map(|p| {
+            if p.is_infinity() {
+                return Point::Infinity;
+            }
+            let z_inv = z_inv.next().expect("one inverse per finite point");
+            Point::Affine { x: p.x.mul(&z_inv), y: p.y.mul(&z_inv.sqr()) }
+        }).collect()
+    }
+
+    /// -(X : Y : Z) = (X : XZ + Y : Z)
+    pub fn ld_neg(&self, p: &LdPoint<F>) -> LdPoint<F> {
+        LdPoint { x: p.x.clone(), y: p.x.mul(&p.z).add(&p.y), z: p.z.clone() }
+    }
+
+    /// 4M + 5S, plus one more multiplication for a not in {0, 1}
+    pub fn ld_double(&self, p: &LdPoint<F>) -> LdPoint<F> {
+        if p.is_infinity() || p.x.is_zero() {
+            return LdPoint::infinity();
+        }
+
+        let x2 = p.x.sqr();
+        let z2 = p.z.sqr();
+        let bz4 = self.b().mul(&z2.sqr());
+        let z3 = x2.mul(&z2);
+        let x3 = x2.sqr().add(&bz4);
+        // Y3 = b Z1^4 Z3 + X3 (a Z3 + Y1^2 + b Z1^4)
+        let y3 = bz4.mul(&z3).add(&x3.mul(&self.times_a(&z3).add(&p.y.sqr()).add(&bz4)));
+
+        LdPoint { x: x3, y: y3, z: z3 }
+    }
+
+    /// LD + affine, 8M + 5S
+    pub fn ld_add_mixed(&self, p: &LdPoint<F>, q: &Point<F>) -> LdPoint<F> {
+        let (x2, y2) = match q {
+            Point::Infinity => return p.clone(),
+            Point::Affine { x, y } => (x, y),
+        };
+        if p.is_infinity() {
+            return LdPoint::from(q);
+        }
+
+        let z1_2 = p.z.sqr();
+        let a = y2.mul(&z1_2).add(&p.y);
+        let b = x2.mul(&p.z).add(&p.x);
+        if b.is_zero() {
+            return if a.is_zero() { self.ld_double(&LdPoint::from(q)) } else { LdPoint::infinity() };
+        }
+
+        let c = p.z.mul(&b);
+        let d = b.sqr().mul(&c.add(&self.times_a(&z1_2)));
+        let z3 = c.sqr();
+        let e = a.mul(&c);
+        let x3 = a.sqr().add(&d).add(&e);
+        let f = x3.add(&x2.mul(&z3));
+        let g = x2.add(y2).mul(&z3.sqr());
+        let y3 = e.add(&z3).mul(&f).add(&g);
+
+        LdPoint { x: x3, y: y3, z: z3 }
+    }
+
+    /// LD + LD, 13M + 6S
+    pub fn ld_add(&self, p: &LdPoint<F>, q: &LdPoint<F>) -> LdPoint<F> {
+        if p.is_infinity() {
+            return q.clone();
+        }
+        if q.is_infinity() {
+            return p.clone();
+        }
+
+        let z1_2 = p.z.sqr();
+        let z2_2 = q.z.sqr();
+        let a0 = q.y.mul(&z1_2);
+        let a1 = p.y.mul(&z2_2);
+        let b0 = q.x.mul(&p.z);
+        let b1 = p.x.mul(&q.z);
+        let c = a0.add(&a1);
+        let d = b0.add(&b1);
+        if d.is_zero() {
+            return if c.is_zero() { self.ld_double(p) } else { LdPoint::infinity() };
+        }
+
+        let e = p.z.mul(&q.z);
+        let f = d.mul(&e);
+        let z3 = f.sqr();
+        let d2 = d.sqr();
+        let g = d2.mul(&f.add(&self.times_a(&e.sqr())));
+        let h = c.mul(&f);
+        let x3 = c.sqr().add(&g).add(&h);
+        let i = d2.mul(&b0).mul(&e).add(&x3);
+        let j = d2.mul(&a0).add(&x3);
+        let y3 = h.mul(&i).add(&z3.mul(&j));
+
+        LdPoint { x: x3, y: y3, z: z3 }
+    }
+
+    /// k P with double-and-add in LD coordinates and mixed additions, one inversion at the end
+    pub fn ld_mul(&self, p: &Point<F>, k: &UnsignedLongInt) -> Point<F> {
+        let mut out = LdPoint::infinity();
+        let Some(top) = k.get_highest_set_bit() else {
+            return Point::Infinity;
+        };
+
+        for i in (0..top + 1).rev() {
+            out = self.ld_double(&out);
+            if k.get_bit(i) {
+                out = self.ld_add_mixed(&out, p);
+            }
+        }
+        self.to_affine(&out)
+    }
+
+    // a is 0 or 1 for most standard curves, so skip the multiplication then
+    fn times_a(&self, el: &F) -> F {
+        if self.a().is_zero() {
+            F::zero()
+        } else if self.a() == &F::one() {
+            el.clone()
+        } else {
+            self.a().mul(el)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::curve::tests::{point_after, test_curve};
+    use super::*;
+
+    #[test]
+    fn cross_check_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let p = point_after(&curve, 2);
+        let q = point_after(&curve, p.x().unwrap().data[0] + 1);
+        let (p_ld, q_ld) = (LdPoint::from(&p), LdPoint::from(&q));
+
+        assert_eq!(curve.to_affine(&curve.ld_double(&p_ld)), curve.double(&p));
+        assert_eq!(curve.to_affine(&curve.ld_add_mixed(&p_ld, &q)), curve.add(&p, &q));
+        assert_eq!(curve.to_affine(&curve.ld_neg(&p_ld)), curve.neg(&p));
+
+        // non-trivial Z on both sides
+        let p2 = curve.ld_double(&p_ld);
+        let q2 = curve.ld_double(&q_ld);
+        assert_eq!(curve.to_affine(&curve.ld_add(&p2, &q2)), curve.add(&curve.double(&p), &curve.double(&q)));
+        assert_eq!(curve.to_affine(&curve.ld_add(&p2, &p2)), curve.to_affine(&curve.ld_double(&p2)));
+        assert!(curve.ld_add(&p2, &curve.ld_neg(&p2)).is_infinity());
+        assert_eq!(curve.ld_add_mixed(&LdPoint::infinity(), &p), p_ld);
+
+        for _ in 0..2 {
+            let k = UnsignedLongInt::from(rand::random::<u64>() % 1024);
+            assert_eq!(curve.ld_mul(&p, &k), curve.mul(&p, &k));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn linearity_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let p = point_after(&curve, 2);
+
+        let k1 = UnsignedLongInt::from(rand::random::<u64>() >> 1);
+        let k2 = UnsignedLongInt::from(rand::random::<u64>() >> 1);
+        let sum = curve.ld_mul(&p, &k1.add(&k2));
+        assert_eq!(sum, curve.add(&curve.ld_mul(&p, &k1), &curve.ld_mul(&p, &k2)));
+        assert!(curve.is_on_curve(&sum));
+        assert_eq!(curve.ld_mul(&p, &UnsignedLongInt::from(0)), Point::Infinity);
+
+        Ok(())
+    }
+}
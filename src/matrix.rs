@@ -0,0 +1,446 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::field::{batch_inverse, BinaryField};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MatrixError {
+    DimensionMismatch,
+    NotSquare,
+    Singular,
+    NoSolution,
+}
+
+impl Display for MatrixError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DimensionMismatch => write!(f, "matrix dimensions don't match"),
+            Self::NotSquare => write!(f, "matrix is not square"),
+            Self::Singular => write!(f, "matrix is singular"),
+            Self::NoSolution => write!(f, "system has no solution"),
+        }
+    }
+}
+
+impl Error for MatrixError {}
+
+/// Dense row-major matrix over a binary field
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Matrix<F: BinaryField> {
+    rows: usize,
+    cols: usize,
+    data: Vec<F>,
+}
+
+impl<F: BinaryField> Matrix<F> {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![F::zero(); rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut m = Self::new(n, n);
+        for i in 0..n {
+            m.set(i, i, F::one());
+        }
+        m
+    }
+
+    pub fn from_rows(rows: Vec<Vec<F>>) -> Result<Self, MatrixError> {
+        let cols = rows.first().map_or(0, |r| r.len());
+        if rows.iter().any(|r| r.len() != cols) {
+            return Err(MatrixError::DimensionMismatch);
+        }
+
+        Ok(Self {
+            rows: rows.len(),
+            cols,
+            data: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Matrix with rows `(1, x_i, x_i^2, ..., x_i^(cols-1))`
+    pub fn vandermonde(points: &[F], cols: usize) -> Self {
+        let mut m = Self::new(points.len(), cols);
+        for (i, x) in points.iter().enumerate() {
+            let mut p = F::one();
+            for j in 0..cols {
+                m.set(i, j, p.clone());
+                p = p.mul(x);
+            }
+        }
+        m
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, r: usize, c: usize) -> &F {
+        &self.data[r * self.cols + c]
+    }
+
+    pub fn set(&mut self, r: usize, c: usize, value: F) {
+        self.data[r * self.cols + c] = value;
+    }
+
+    pub fn row(&self, r: usize) -> &[F] {
+        &self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    pub fn add(&self, other: &Self) -> Result<Self, MatrixError> {
+        if self.rows != other.rows || self.cols != other.cols {
+            return Err(MatrixError::DimensionMismatch);
+        }
+
+        Ok(Self {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().zip(other.data.iter()).map(|(a, b)| a.add(b)).collect(),
+        })
+    }
+
+    pub fn mul(&self, other: &Self) -> Result<Self, MatrixError> {
+        if self.cols != other.rows {
+            return Err(MatrixError::DimensionMismatch);
+        }
+
+        let mut out = Self::new(self.rows, other.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self.get(i, k);
+                if a.is_zero() {
+                    continue;
+                }
+                for j in 0..other.cols {
+                    let v = out.get(i, j).add(&a.mul(other.get(k, j)));
+                    out.set(i, j, v);
+                }
+            }
+        }
+
+        Ok(out)
+    }
+
+    pub fn mul_vec(&self, v: &[F]) -> Result<Vec<F>, MatrixError> {
+        if self.cols != v.len() {
+            return Err(MatrixError::DimensionMismatch);
+        }
+
+        Ok((0..self.rows)
+            .map(|i| {
+                self.row(i).iter().zip(v.iter())
+                    .fold(F::zero(), |acc, (a, b)| acc.add(&a.mul(b)))
+            })
+            .collect())
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut out = Self::new(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                out.set(j, i, self.get(i, j).clone());
+            }
+        }
+        out
+    }
+
+    /// Reduced row echelon form and the pivot columns
+    pub fn reduced_row_echelon(&self) -> (Self, Vec<usize>) {
+        let mut m = self.clone();
+        let (pivots, _) = m.eliminate(self.cols);
+        (m, pivots)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.reduced_row_echelon().1.len()
+    }
+
+    pub fn determinant(&self) -> Result<F, MatrixError> {
+        if self.rows != self.cols {
+            return Err(MatrixError::NotSquare);
+        }
+
+        let mut m = self.clone();
+        let (pivots, det) = m.eliminate(self.cols);
+        if pivots.len() < self.rows {
+            return Ok(F::zero());
+        }
+
+        // row swaps don't change the sign in characteristic 2
+        Ok(det)
+    }
+
+    pub fn inverse(&self) -> Result<Self, MatrixError> {
+        if self.rows != self.cols {
+            return Err(MatrixError::NotSquare);
+        }
+        let n = self.rows;
+
+        let mut aug = Self::new(n, 2 * n);
+        for i in 0..n {
+            for j in 0..n {
+                aug.set(i, j, self.get(i, j).clone());
+            }
+            aug.set(i, n + i, F::one());
+        }
+
+        let (pivots, _) = aug.eliminate(n);
+        if pivots.len() < n {
+            return Err(MatrixError::Singular);
+        }
+
+        let mut out = Self::new(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                out.set(i, j, aug.get(i, n + j).clone());
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Basis of the right null space {v : self * v = 0}
+    pub fn kernel(&self) -> Vec<Vec<F>> {
+        let (rref, pivots) = self.reduced_row_echelon();
+
+        let mut basis = Vec::new();
+        for free in (0..self.cols).filter(|c| !pivots.contains(c)) {
+            let mut v = vec![F::zero(); self.cols];
+            v[free] = F::one();
+            for (r, &p) in pivots.iter().enumerate() {
+                v[p] = rref.get(r, free).clone();
+            }
+            basis.push(v);
+        }
+
+        basis
+    }
+
+    /// One solution of self * x = b, free variables are set to zero
+    pub fn solve(&self, b: &[F]) -> Result<Vec<F>, MatrixError> {
+        if b.len() != self.rows {
+            return Err(MatrixError::DimensionMismatch);
+        }
+
+        let mut aug = Self::new(self.rows, self.cols + 1);
+        for (i, bi) in b.iter().enumerate() {
+            for j in 0..self.cols {
+                aug.set(i, j, self.get(i, j).clone());
+            }
+            aug.set(i, self.cols, bi.clone());
+        }
+
+        let (pivots, _) = aug.eliminate(self.cols);
+        if (pivots.len()..self.rows).any(|r| !aug.get(r, self.cols).is_zero()) {
+            return Err(MatrixError::NoSolution);
+        }
+
+        let mut x = vec![F::zero(); self.cols];
+        for (r, &p) in pivots.iter().enumerate() {
+            x[p] = aug.get(r, self.cols).clone();
+        }
+
+        Ok(x)
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for j in 0..self.cols {
+            self.data.swap(a * self.cols + j, b * self.cols + j);
+        }
+    }
+
+    // Gauss-Jordan elimination over the first `limit` columns, returns the pivot
+    // columns and the product of the pivots before normalization
+    fn eliminate(&mut self, limit: usize) -> (Vec<usize>, F) {
+        let mut pivots = Vec::new();
+        let mut det = F::one();
+        let mut row = 0;
+
+        for col in 0..limit {
+            if row == self.rows {
+                break;
+            }
+            let Some(p) = (row..self.rows).find(|&r| !self.get(r, col).is_zero()) else {
+                continue;
+            };
+            self.swap_rows(p, row);
+
+            let pivot = self.get(row, col).clone();
+            let inv = pivot.inverse();
+            det = det.mul(&pivot);
+            for j in col..self.cols {
+                let v = self.get(row, j).mul(&inv);
+                self.set(row, j, v);
+            }
+
+            for r in 0..self.rows {
+                if r == row {
+                    continue;
+                }
+                let factor = self.get(r, col).clone();
+                if factor.is_zero() {
+                    continue;
+                }
+                for j in col..self.cols {
+                    let v = self.get(r, j).add(&factor.mul(self.get(row, j)));
+                    self.set(r, j, v);
+                }
+            }
+
+            pivots.push(col);
+            row += 1;
+        }
+
+        (pivots, det)
+    }
+}
+
+/// Solves the Vandermonde system sum_j c_j * x_i^j = y_i (interpolation) with O(n^2) operations
+/// and a single field inversion, points must be distinct
+pub fn solve_vandermonde<F: BinaryField>(points: &[F], values: &[F]) -> Result<Vec<F>, MatrixError> {
+    let n = points.len();
+    if values.len() != n {
+        return Err(MatrixError::DimensionMismatch);
+    }
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+
+    // master polynomial P(x) = prod (x - x_i)
+    let mut master = vec![F::one()];
+    for x in points {
+        let mut next = vec![F::zero(); master.len() + 1];
+        for (k, c) in master.iter().enumerate() {
+            next[k + 1] = next[k + 1].add(c);
+            next[k] = next[k].add(&c.mul(x));
+        }
+        master = next;
+    }
+
+    // Q_i(x) = P(x) / (x - x_i) by synthetic division, and Q_i(x_i)
+    let mut quotients = Vec::with_capacity(n);
+    let mut denominators = Vec::with_capacity(n);
+    for x in points {
+        let mut q = vec![F::zero(); n];
+        let mut carry = F::zero();
+        for k in (0..n).rev() {
+            carry = master[k + 1].add(&carry.mul(x));
+            q[k] = carry.clone();
+        }
+
+        let d = q.iter().rev().fold(F::zero(), |acc, c| acc.mul(x).add(c));
+        if d.is_zero() {
+            return Err(MatrixError::Singular);
+        }
+        quotients.push(q);
+        denominators.push(d);
+    }
+
+    let inverses = batch_inverse(&denominators);
+    let mut coefs = vec![F::zero(); n];
+    for i in 0..n {
+        let scale = values[i].mul(&inverses[i]);
+        for (c, q) in coefs.iter_mut().zip(quotients[i].iter()) {
+            *c = c.add(&q.mul(&scale));
+        }
+    }
+
+    Ok(coefs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::str::FromStr;
+    use crate::{GF2Element, DIM};
+    use super::*;
+
+    fn sample() -> Result<Vec<GF2Element<DIM>>, Box<dyn Error>> {
+        Ok(vec![
+            GF2Element::from_str("050E04B10B1CA453CDA09E9F9EAF055A3F6EE35A162EC9E390CC67888B2FDE0EA1DD5B2A2C9E6E373F3DE18B5621FF810F0C338D71")?,
+            GF2Element::from_str("066A1CDA81DFBD5953500236E1D5264911779ECCBCBF1241AC2886FF71AB374B7DD0A28E6863801FF40507229FE65223587491D2CD")?,
+            GF2Element::from_str("04C1190B05D7B06470D4D030368B91BF48FBC8D207BF309F7CB87C21451DABCD293D5A560A437808BDF4184C96951A1B3F698FBD70")?,
+        ])
+    }
+
+    #[test]
+    fn inverse_test() -> Result<(), Box<dyn Error>> {
+        let s = sample()?;
+        let a = Matrix::from_rows(vec![
+            vec![s[0].clone(), s[1].clone(), GF2Element::ONE],
+            vec![GF2Element::ZERO, s[2].clone(), s[0].clone()],
+            vec![s[1].clone(), GF2Element::ONE, GF2Element::ZERO],
+        ])?;
+
+        let inv = a.inverse()?;
+        assert_eq!(a.mul(&inv)?, Matrix::identity(3));
+        assert_eq!(a.rank(), 3);
+        assert!(!a.determinant()?.is_zero());
+
+        Ok(())
+    }
+
+    #[test]
+    fn singular_test() -> Result<(), Box<dyn Error>> {
+        let s = sample()?;
+        // third row is the sum of the first two, the second one is s[1] times the first one
+        let r0 = vec![GF2Element::ONE, s[0].clone(), s[2].clone()];
+        let r1: Vec<GF2Element<DIM>> = r0.iter().map(|x| x.mul(&s[1])).collect();
+        let r2: Vec<GF2Element<DIM>> = r0.iter().zip(r1.iter()).map(|(x, y)| x.add(y)).collect();
+        let a = Matrix::from_rows(vec![r0, r1, r2])?;
+
+        assert_eq!(a.rank(), 1);
+        assert_eq!(a.determinant()?, GF2Element::ZERO);
+        assert_eq!(a.inverse(), Err(MatrixError::Singular));
+
+        let kernel = a.kernel();
+        assert_eq!(kernel.len(), 2);
+        for v in &kernel {
+            assert!(a.mul_vec(v)?.iter().all(|x| x.is_zero()));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn solve_test() -> Result<(), Box<dyn Error>> {
+        let s = sample()?;
+        let a = Matrix::from_rows(vec![
+            vec![s[0].clone(), GF2Element::ONE],
+            vec![s[1].clone(), s[2].clone()],
+        ])?;
+        let b = vec![s[2].clone(), GF2Element::ONE];
+
+        let x = a.solve(&b)?;
+        assert_eq!(a.mul_vec(&x)?, b);
+
+        let degenerate = Matrix::from_rows(vec![
+            vec![s[0].clone(), GF2Element::ONE],
+            vec![s[0].clone(), GF2Element::ONE],
+        ])?;
+        assert_eq!(degenerate.solve(&b), Err(MatrixError::NoSolution));
+
+        Ok(())
+    }
+
+    #[test]
+    fn vandermonde_test() -> Result<(), Box<dyn Error>> {
+        let points = sample()?;
+        let values = vec![GF2Element::ONE, points[2].clone(), GF2Element::ZERO];
+
+        let c = solve_vandermonde(&points, &values)?;
+        assert_eq!(Matrix::vandermonde(&points, 3).mul_vec(&c)?, values);
+
+        let repeated = vec![points[0].clone(), points[0].clone()];
+        assert_eq!(solve_vandermonde(&repeated, &values[..2]), Err(MatrixError::Singular));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{deg, GF2Element, DIM, MOD, MOD_DEG};
+
+const WORD: usize = usize::BITS as usize;
+
+// Polynomials over GF(2) are stored like the field modulus: bit i is the coefficient at x^i.
+// Everything here has degree at most MOD_DEG, so DIM limbs are always enough.
+
+impl GF2Element<DIM> {
+    /// Minimal polynomial over GF(2) via Berlekamp-Massey on the sequence Tr(a^i)
+    pub fn minimal_polynomial(&self) -> [usize; DIM] {
+        let functional = trace_functional();
+
+        let mut sequence = Vec::with_capacity(2 * MOD_DEG);
+        let mut p = GF2Element::ONE;
+        for _ in 0..2 * MOD_DEG {
+            sequence.push(trace_with(&functional, &p));
+            p = p.mul(self);
+        }
+
+        berlekamp_massey(&sequence)
+    }
+
+    /// Minimal polynomial over GF(2) as the product of (x - a^(2^i)) over the Frobenius orbit.
+    /// Takes O(d^2) multiplications for an element of degree d.
+    pub fn minimal_polynomial_orbit(&self) -> [usize; DIM] {
+        let mut conjugate = self.clone();
+        // coefficients from the lowest degree
+        let mut product = vec![GF2Element::ONE];
+        loop {
+            let mut next = vec![GF2Element::ZERO; product.len() + 1];
+            for (k, c) in product.iter().enumerate() {
+                next[k + 1] = next[k + 1].add(c);
+                next[k] = next[k].add(&c.mul(&conjugate));
+            }
+            product = next;
+
+            conjugate = conjugate.sqr();
+            if &conjugate == self {
+                break;
+            }
+        }
+
+        let mut out = [0; DIM];
+        for (k, c) in product.iter().enumerate() {
+            if c == &GF2Element::ONE {
+                out[k / WORD] |= 1 << (k % WORD);
+            } else {
+                assert_eq!(c, &GF2Element::ZERO, "coefficients of the orbit product must be in GF(2)");
+            }
+        }
+        out
+    }
+
+    /// Characteristic polynomial of multiplication by the element, the minimal polynomial to the power m / d
+    pub fn characteristic_polynomial(&self) -> [usize; DIM] {
+        let minimal = self.minimal_polynomial();
+        let times = MOD_DEG / deg(&minimal);
+
+        let mut out = [0; DIM];
+        out[0] = 1;
+        for _ in 0..times {
+            out = poly_mul(&out, &minimal);
+        }
+        out
+    }
+
+    /// Degree of the smallest subfield GF(2^d) containing the element, the smallest d with a^(2^d) = a
+    pub fn subfield_degree(&self) -> usize {
+        let mut conjugate = self.sqr();
+        let mut d = 1;
+        while &conjugate != self {
+            conjugate = conjugate.sqr();
+            d += 1;
+        }
+        d
+    }
+}
+
+/// Evaluates a GF(2) polynomial at a field element with Horner's scheme
+pub fn evaluate(poly: &[usize; DIM], el: &GF2Element<DIM>) -> GF2Element<DIM> {
+    let top = deg(poly);
+    let mut out = GF2Element::ZERO;
+    for i in (0..top + 1).rev() {
+        out = out.mul(el);
+        if poly[i / WORD] & (1 << (i % WORD)) != 0 {
+            out = out.add(&GF2Element::ONE);
+        }
+    }
+    out
+}
+
+// Bits Tr(x^j) for the polynomial basis, so Tr(a) is the parity of a & functional.
+// Power sums of the roots of MOD come from Newton's identities, in characteristic 2
+// p_k = e_1 p_(k-1) + ... + e_(k-1) p_1 + k e_k with e_i the coefficient at x^(m-i).
+fn trace_functional() -> [usize; DIM] {
+    let e = |i: usize| MOD[(MOD_DEG - i) / WORD] & (1 << ((MOD_DEG - i) % WORD)) != 0;
+
+    let mut p = vec![false; MOD_DEG];
+    p[0] = MOD_DEG % 2 == 1;
+    for k in 1..MOD_DEG {
+        let mut acc = k % 2 == 1 && e(k);
+        for i in 1..k {
+            acc ^= e(i) && p[k - i];
+        }
+        p[k] = acc;
+    }
+
+    let mut out = [0; DIM];
+    for (j, &bit) in p.iter().enumerate() {
+        if bit {
+            out[j / WORD] |= 1 << (j % WORD);
+        }
+    }
+    out
+}
+
+fn trace_with(functional: &[usize; DIM], el: &GF2Element<DIM>) -> bool {
+    functional.iter().zip(el.data.iter())
+        .fold(0, |acc, (a, b)| acc ^ (a & b).count_ones()) & 1 == 1
+}
+
+// Returns the minimal polynomial of the sequence, the reciprocal of the connection polynomial
+fn berlekamp_massey(s: &[bool]) -> [usize; DIM] {
+    let mut c = vec![false; s.len() + 1];
+    let mut b = vec![false; s.len() + 1];
+    c[0] = true;
+    b[0] = true;
+    let mut l = 0;
+    let mut shift = 1;
+
+    for n in 0..s.len() {
+        let mut discrepancy = s[n];
+        for i in 1..l + 1 {
+            discrepancy ^= c[i] && s[n - i];
+        }
+
+        if !discrepancy {
+            shift += 1;
+            continue;
+        }
+
+        let previous = c.clone();
+        for i in 0..s.len() + 1 - shift {
+            c[i + shift] ^= b[i];
+        }
+        if 2 * l <= n {
+            l = n + 1 - l;
+            b = previous;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+    }
+
+    let mut out = [0; DIM];
+    for i in 0..l + 1 {
+        if c[i] {
+            out[(l - i) / WORD] |= 1 << ((l - i) % WORD);
+        }
+    }
+    out
+}
+
+fn poly_mul(a: &[usize; DIM], b: &[usize; DIM]) -> [usize; DIM] {
+    let mut out = [0; DIM];
+    for i in 0..deg(a) + 1 {
+        if a[i / WORD] & (1 << (i % WORD)) == 0 {
+            continue;
+        }
+        for j in 0..deg(b) + 1 {
+            if b[j / WORD] & (1 << (j % WORD)) != 0 {
+                out[(i + j) / WORD] ^= 1 << ((i + j) % WORD);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::str::FromStr;
+    use super::*;
+
+    #[test]
+    fn modulus_test() -> Result<(), Box<dyn Error>> {
+        let x = GF2Element::from_str("2")?;
+        assert_eq!(x.minimal_polynomial(), MOD);
+        assert_eq!(x.characteristic_polynomial(), MOD);
+        assert_eq!(x.subfield_degree(), MOD_DEG);
+
+        Ok(())
+    }
+
+    #[test]
+    fn trace_functional_test() -> Result<(), Box<dyn Error>> {
+        let functional = trace_functional();
+        let p = GF2Element::from_str("073A5F1662A5634B30ABEF467039D38245C795D6C50B1600C2DA169BB74C819156CE000BDE8DDA14C395923ABBBE4EFA8BEC80EAE9")?;
+        assert!(trace_with(&functional, &p));
+        assert!(!trace_with(&functional, &p.sqr().add(&p)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn random_element_test() -> Result<(), Box<dyn Error>> {
+        let a = GF2Element::from_str("050E04B10B1CA453CDA09E9F9EAF055A3F6EE35A162EC9E390CC67888B2FDE0EA1DD5B2A2C9E6E373F3DE18B5621FF810F0C338D71")?;
+        let minimal = a.minimal_polynomial();
+        assert_eq!(deg(&minimal), MOD_DEG);
+        assert_eq!(evaluate(&minimal, &a), GF2Element::ZERO);
+
+        Ok(())
+    }
+
+    #[test]
+    fn prime_subfield_test() {
+        // 1 has minimal polynomial x + 1, 0 has x
+        assert_eq!(GF2Element::ONE.minimal_polynomial(), [0b11, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(GF2Element::ONE.minimal_polynomial_orbit(), [0b11, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(GF2Element::ZERO.minimal_polynomial(), [0b10, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(GF2Element::ZERO.minimal_polynomial_orbit(), [0b10, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(GF2Element::ONE.subfield_degree(), 1);
+
+        // x^419 alone
+        let mut x_m = [0; DIM];
+        x_m[MOD_DEG / WORD] = 1 << (MOD_DEG % WORD);
+        assert_eq!(GF2Element::ZERO.characteristic_polynomial(), x_m);
+    }
+}
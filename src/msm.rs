@@ -0,0 +1,157 @@
+use vl_big_ints::UnsignedLongInt;
+use crate::curve::{Curve, Point};
+use crate::field::BinaryField;
+use crate::lopez_dahab::LdPoint;
+
+/// Largest number of terms for `straus_mul`, its table holds 2^t - 1 subset sums
+pub const STRAUS_MAX_POINTS: usize = 3;
+
+/// Pippenger window for the given number of terms, about log2(n) - 1 bits
+pub fn pippenger_window(terms: usize) -> usize {
+    (terms.max(1).ilog2() as usize).saturating_sub(1).clamp(2, 16)
+}
+
+impl<F: BinaryField> Curve<F> {
+    /// k P + l Q with Shamir's trick
+    pub fn shamir_mul(&self, p: &Point<F>, k: &UnsignedLongInt, q: &Point<F>, l: &UnsignedLongInt) -> Point<F> {
+        self.straus_mul(&[(p, k), (q, l)])
+    }
+
+    /// Sum of k_i P_i with one shared chain of doublings: every step adds the precomputed sum
+    /// of the points whose scalars have the current bit set
+    pub fn straus_mul(&self, terms: &[(&Point<F>, &UnsignedLongInt)]) -> Point<F> {
+        assert!(terms.len() <= STRAUS_MAX_POINTS, "Straus table supports up to {STRAUS_MAX_POINTS} points");
+        let Some(top) = terms.iter().filter_map(|(_, k)| k.get_highest_set_bit()).max() else {
+            return Point::Infinity;
+        };
+
+        // table[mask] is the sum of P_i over the bits i of mask
+        let mut table = vec![LdPoint::infinity()];
+        for mask in 1usize..1 << terms.len() {
+            let low = mask.trailing_zeros() as usize;
+            table.push(self.ld_add_mixed(&table[mask & (mask - 1)], terms[low].0));
+        }
+
+        let mut out = LdPoint::infinity();
+        for i in (0..top + 1).rev() {
+            out = self.ld_double(&out);
+            let mask = terms.iter().enumerate()
+                .filter(|(_, (_, k))| k.get_bit(i))
+                .fold(0, |acc, (j, _)| acc | 1 << j);
+            if mask != 0 {
+                out = self.ld_add(&out, &table[mask]);
+            }
+        }
+        self.to_affine(&out)
+    }
+
+    /// Sum of k_i P_i with Pippenger's bucket method: for every window of `window` scalar bits
+    /// the points are sorted into buckets by their digit, and the buckets are combined
+    /// with running sums into sum(d * bucket[d])
+    pub fn pippenger_mul(&self, terms: &[(&Point<F>, &UnsignedLongInt)], window: usize) -> Point<F> {
+        assert!((1..=16).contains(&window), "bucket window must be between 1 and 16");
+        let Some(top) = terms.iter().filter_map(|(_, k)| k.get_highest_set_bit()).max() else {
+            return Point::Infinity;
+        };
+
+        let mut out = LdPoint::infinity();
+        for w in (0..(top + 1).div_ceil(window)).rev() {
+            for _ in 0..window {
+                out = self.ld_double(&out);
+            }
+
+            let mut buckets = vec![LdPoint::infinity(); 1 << window];
+            for (p, k) in terms {
+                let digit = (0..window).filter(|&j| k.get_bit(w * window + j)).fold(0, |acc, j| acc | 1 << j);
+                if digit != 0 {
+                    buckets[digit] = self.ld_add_mixed(&buckets[digit], p);
+                }
+            }
+
+            // running = bucket[d] + ... + bucket[2^c - 1] is added once for every d
+            let mut running = LdPoint::infinity();
+            let mut sum = LdPoint::infinity();
+            for bucket in buckets[1..].iter().rev() {
+                running = self.ld_add(&running, bucket);
+                sum = self.ld_add(&sum, &running);
+            }
+            out = self.ld_add(&out, &sum);
+        }
+        self.to_affine(&out)
+    }
+
+    /// Sum of k_i P_i: Straus for a few terms, Pippenger with `pippenger_window` otherwise
+    pub fn multi_mul(&self, terms: &[(&Point<F>, &UnsignedLongInt)]) -> Point<F> {
+        if terms.len() <= STRAUS_MAX_POINTS {
+            self.straus_mul(terms)
+        } else {
+            self.pippenger_mul(terms, pippenger_window(terms.len()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::curve::tests::{point_after, test_curve};
+    use crate::MOD_DEG;
+    use super::*;
+
+    fn points(curve: &Curve, count: usize) -> Vec<Point> {
+        let mut out = vec![point_after(curve, 2)];
+        while out.len() < count {
+            let next = out.last().unwrap().x().unwrap().data[0] + 1;
+            out.push(point_after(curve, next));
+        }
+        out
+    }
+
+    // sum of k_i P_i one term at a time
+    fn naive(curve: &Curve, terms: &[(&Point, &UnsignedLongInt)]) -> Point {
+        terms.iter().fold(Point::Infinity, |acc, (p, k)| curve.add(&acc, &curve.ld_mul(p, k)))
+    }
+
+    #[test]
+    fn straus_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let ps = points(&curve, 3);
+        let ks: Vec<_> = (0..3).map(|_| UnsignedLongInt::from(rand::random::<u64>())).collect();
+
+        for t in 1..=3 {
+            let terms: Vec<_> = ps.iter().zip(&ks).take(t).collect();
+            assert_eq!(curve.straus_mul(&terms), naive(&curve, &terms));
+        }
+
+        // full length scalars, a zero scalar and k P + (-k) P
+        let k = UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(MOD_DEG as u64)).sub(&ks[0]);
+        let zero = UnsignedLongInt::from(0);
+        assert_eq!(curve.shamir_mul(&ps[0], &k, &ps[1], &zero), curve.wnaf_mul(&ps[0], &k, 4));
+        assert_eq!(curve.shamir_mul(&ps[0], &ks[1], &curve.neg(&ps[0]), &ks[1]), Point::Infinity);
+        assert_eq!(curve.straus_mul(&[]), Point::Infinity);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pippenger_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let ps = points(&curve, 12);
+        let mut ks: Vec<_> = (0..12).map(|_| UnsignedLongInt::from(rand::random::<u64>())).collect();
+        ks[3] = UnsignedLongInt::from(0);
+        let terms: Vec<_> = ps.iter().zip(&ks).collect();
+
+        let expected = naive(&curve, &terms);
+        for window in [1, 3, pippenger_window(terms.len()), 7] {
+            assert_eq!(curve.pippenger_mul(&terms, window), expected);
+        }
+        assert_eq!(curve.multi_mul(&terms), expected);
+        assert_eq!(curve.multi_mul(&terms[..2]), naive(&curve, &terms[..2]));
+
+        // repeated points land in the same bucket
+        let p = &ps[0];
+        let (a, b) = (UnsignedLongInt::from(5), UnsignedLongInt::from(13));
+        assert_eq!(curve.pippenger_mul(&[(p, &a), (p, &b), (p, &a), (p, &b)], 2), curve.ld_mul(p, &UnsignedLongInt::from(36)));
+
+        Ok(())
+    }
+}
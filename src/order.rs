@@ -0,0 +1,210 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use vl_big_ints::UnsignedLongInt;
+use crate::long_int::div_rem;
+use crate::{GF2Element, DIM, MOD_DEG};
+
+/// Prime factorization as (prime, exponent) pairs
+pub type Factorization = Vec<(UnsignedLongInt, usize)>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OrderError {
+    ZeroElement,
+    NotAMultiple,
+    InvalidFactorization,
+    NotADivisor,
+    UnknownFactorization,
+}
+
+impl Display for OrderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ZeroElement => write!(f, "zero is not in the multiplicative group"),
+            Self::NotAMultiple => write!(f, "factorized number is not a multiple of the element order"),
+            Self::InvalidFactorization => write!(f, "factorization doesn't match the group order"),
+            Self::NotADivisor => write!(f, "subgroup order doesn't divide the group order"),
+            Self::UnknownFactorization => write!(f, "no built-in factorization for this field"),
+        }
+    }
+}
+
+impl Error for OrderError {}
+
+/// 2^m - 1, the order of the multiplicative group
+pub fn group_order() -> UnsignedLongInt {
+    UnsignedLongInt::from(2)
+        .pow(&UnsignedLongInt::from(MOD_DEG as u64))
+        .sub(&UnsignedLongInt::from(1))
+}
+
+// Complete factorizations of 2^m - 1 as (m, [(prime in hex, exponent)])
+const KNOWN_FACTORIZATIONS: &[(usize, &[(&str, usize)])] = &[
+    (8, &[("3", 1), ("5", 1), ("11", 1)]),
+    (16, &[("3", 1), ("5", 1), ("11", 1), ("101", 1)]),
+    (419, &[
+        ("347", 1),
+        ("335FBAF24B561", 1),
+        ("4CC498BA886C05F62D297", 1),
+        ("7BD640726DC50DD9FB87DAEB6FD2679", 1),
+        ("53DA2E92E159407C943923D09880963C2A04477", 1),
+    ]),
+];
+
+/// Built-in factorization of 2^m - 1 for GF(2^8), GF(2^16) and the configured GF(2^419)
+pub fn known_factorization(m: usize) -> Result<Factorization, OrderError> {
+    KNOWN_FACTORIZATIONS.iter()
+        .find(|(degree, _)| *degree == m)
+        .map(|(_, factors)| factors.iter().map(|(p, e)| (UnsignedLongInt::from_str(p).expect("table primes are hex"), *e)).collect())
+        .ok_or(OrderError::UnknownFactorization)
+}
+
+pub fn factorization_value(factorization: &[(UnsignedLongInt, usize)]) -> UnsignedLongInt {
+    factorization.iter().fold(UnsignedLongInt::from(1), |acc, (p, e)| {
+        acc.mul(&p.pow(&UnsignedLongInt::from(*e as u64)))
+    })
+}
+
+impl GF2Element<DIM> {
+    /// Multiplicative order given the factorization of any multiple of it, normally of 2^m - 1
+    pub fn order(&self, factorization: &[(UnsignedLongInt, usize)]) -> Result<UnsignedLongInt, OrderError> {
+        if self == &GF2Element::ZERO {
+            return Err(OrderError::ZeroElement);
+        }
+
+        let mut order = factorization_value(factorization);
+        if self.pow(&order) != GF2Element::ONE {
+            return Err(OrderError::NotAMultiple);
+        }
+
+        for (p, e) in factorization {
+            for _ in 0..*e {
+                let (reduced, _) = div_rem(&order, p);
+                if self.pow(&reduced) != GF2Element::ONE {
+                    break;
+                }
+                order = reduced;
+            }
+        }
+
+        Ok(order)
+    }
+
+    /// Whether the element generates the whole multiplicative group, takes the factorization of 2^m - 1
+    pub fn is_primitive(&self, factorization: &[(UnsignedLongInt, usize)]) -> Result<bool, OrderError> {
+        let n = group_order();
+        if factorization_value(factorization) != n {
+            return Err(OrderError::InvalidFactorization);
+        }
+        if self == &GF2Element::ZERO {
+            return Ok(false);
+        }
+
+        for (p, _) in factorization {
+            let (cofactor, _) = div_rem(&n, p);
+            if self.pow(&cofactor) == GF2Element::ONE {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// First primitive element among 2, 3, 4, ... read as polynomials, so the result is reproducible
+    pub fn find_primitive_element(factorization: &[(UnsignedLongInt, usize)]) -> Result<Self, OrderError> {
+        for candidate in candidates() {
+            if candidate.is_primitive(factorization)? {
+                return Ok(candidate);
+            }
+        }
+
+        unreachable!("the multiplicative group is cyclic")
+    }
+
+    /// Element of order exactly r given the factorization of r, r must divide 2^m - 1
+    pub fn subgroup_generator(r_factorization: &[(UnsignedLongInt, usize)]) -> Result<Self, OrderError> {
+        let r = factorization_value(r_factorization);
+        let (cofactor, rem) = div_rem(&group_order(), &r);
+        if rem != UnsignedLongInt::from(0) {
+            return Err(OrderError::NotADivisor);
+        }
+
+        for candidate in candidates() {
+            let g = candidate.pow(&cofactor);
+            if g.order(r_factorization)? == r {
+                return Ok(g);
+            }
+        }
+
+        unreachable!("the multiplicative group is cyclic")
+    }
+}
+
+fn candidates() -> impl Iterator<Item=GF2Element<DIM>> {
+    (2..).map(|k| {
+        let mut el = GF2Element::ZERO;
+        el.data[0] = k;
+        el
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::long_int::is_probable_prime;
+    use super::*;
+
+    #[test]
+    fn subgroup_test() -> Result<(), Box<dyn Error>> {
+        let r = vec![(UnsignedLongInt::from(839), 1)];
+        let g = GF2Element::subgroup_generator(&r)?;
+
+        assert_ne!(g, GF2Element::ONE);
+        assert_eq!(g.pow(&UnsignedLongInt::from(839)), GF2Element::ONE);
+        assert_eq!(g.order(&r)?, UnsignedLongInt::from(839));
+        assert_eq!(g.sqr().order(&r)?, UnsignedLongInt::from(839));
+        assert_eq!(GF2Element::ONE.order(&r)?, UnsignedLongInt::from(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn table_test() {
+        for m in [8, 16, MOD_DEG] {
+            let factorization = known_factorization(m).unwrap();
+            let expected = UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(m as u64)).sub(&UnsignedLongInt::from(1));
+            assert_eq!(factorization_value(&factorization), expected);
+            assert!(factorization.iter().all(|(p, _)| is_probable_prime(p, 20)));
+        }
+    }
+
+    #[test]
+    fn primitive_element_test() -> Result<(), Box<dyn Error>> {
+        let factorization = known_factorization(MOD_DEG)?;
+        let g = GF2Element::find_primitive_element(&factorization)?;
+
+        assert!(g.is_primitive(&factorization)?);
+        assert_eq!(g.order(&factorization)?, group_order());
+        // the search starts at x, a smaller candidate would have been returned
+        assert!(candidates().take_while(|c| c != &g).all(|c| !c.is_primitive(&factorization).unwrap()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors_test() -> Result<(), Box<dyn Error>> {
+        let a = GF2Element::from_str("050E04B10B1CA453CDA09E9F9EAF055A3F6EE35A162EC9E390CC67888B2FDE0EA1DD5B2A2C9E6E373F3DE18B5621FF810F0C338D71")?;
+        let wrong = vec![(UnsignedLongInt::from(839), 1)];
+
+        assert_eq!(GF2Element::ZERO.order(&wrong), Err(OrderError::ZeroElement));
+        assert_eq!(a.order(&wrong), Err(OrderError::NotAMultiple));
+        assert_eq!(a.is_primitive(&wrong), Err(OrderError::InvalidFactorization));
+        assert_eq!(
+            GF2Element::subgroup_generator(&[(UnsignedLongInt::from(3), 1)]),
+            Err(OrderError::NotADivisor)
+        );
+        assert_eq!(known_factorization(163), Err(OrderError::UnknownFactorization));
+
+        Ok(())
+    }
+}
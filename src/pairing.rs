@@ -0,0 +1,260 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use vl_big_ints::UnsignedLongInt;
+use crate::curve::Point;
+use crate::extension::QuarticElement;
+use crate::{GF2Element, DIM, MOD_DEG};
+
+// Loop length of the eta_T pairing is 2^K +- 1
+const K: usize = MOD_DEG.div_ceil(2);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PairingError {
+    UnsupportedCoefficient,
+    NotOnCurve,
+}
+
+impl Display for PairingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedCoefficient => write!(f, "eta_T pairing needs b = 0 or b = 1"),
+            Self::NotOnCurve => write!(f, "point doesn't lie on the curve"),
+        }
+    }
+}
+
+impl Error for PairingError {}
+
+/// Supersingular curve y^2 + y = x^3 + x + b, b in GF(2), with embedding degree 4 for odd m.
+/// #E = 2^m + 1 + nu 2^((m + 1) / 2) with nu = +-1 depending on b and m mod 8.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SupersingularCurve {
+    b: GF2Element<DIM>,
+}
+
+impl SupersingularCurve {
+    pub fn new(b: GF2Element<DIM>) -> Result<Self, PairingError> {
+        if b != GF2Element::ZERO && b != GF2Element::ONE {
+            return Err(PairingError::UnsupportedCoefficient);
+        }
+        Ok(Self { b })
+    }
+
+    pub fn b(&self) -> &GF2Element<DIM> {
+        &self.b
+    }
+
+    /// nu = 1 exactly when b = 1 for m = 3, 5 mod 8 or b = 0 for m = 1, 7 mod 8
+    pub fn nu_is_positive(&self) -> bool {
+        (self.b == GF2Element::ONE) == matches!(MOD_DEG % 8, 3 | 5)
+    }
+
+    pub fn group_order(&self) -> UnsignedLongInt {
+        let two = UnsignedLongInt::from(2);
+        let q_plus_one = two.pow(&UnsignedLongInt::from(MOD_DEG as u64)).add(&UnsignedLongInt::from(1));
+        let half = two.pow(&UnsignedLongInt::from(K as u64));
+        if self.nu_is_positive() { q_plus_one.add(&half) } else { q_plus_one.sub(&half) }
+    }
+
+    /// Affine point after checking the curve equation
+    pub fn point(&self, x: GF2Element<DIM>, y: GF2Element<DIM>) -> Result<Point, PairingError> {
+        let p = Point::Affine { x, y };
+        if self.is_on_curve(&p) { Ok(p) } else { Err(PairingError::NotOnCurve) }
+    }
+
+    pub fn is_on_curve(&self, p: &Point) -> bool {
+        match p {
+            Point::Infinity => true,
+            Point::Affine { x, y } => y.sqr().add(y) == x.sqr().mul(x).add(x).add(&self.b),
+        }
+    }
+
+    /// -(x, y) = (x, y + 1)
+    pub fn neg(&self, p: &Point) -> Point {
+        match p {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x, y } => Point::Affine { x: x.clone(), y: y.add(&GF2Element::ONE) },
+        }
+    }
+
+    pub fn add(&self, p: &Point, q: &Point) -> Point {
+        let (Point::Affine { x: x1, y: y1 }, Point::Affine { x: x2, y: y2 }) = (p, q) else {
+            return if p.is_infinity() { q.clone() } else { p.clone() };
+        };
+        if x1 == x2 {
+            return if y1 == y2 { self.double(p) } else { Point::Infinity };
+        }
+
+        let l = y1.add(y2).mul(&x1.add(x2).inverse());
+        let x3 = l.sqr().add(x1).add(x2);
+        let y3 = l.mul(&x1.add(&x3)).add(y1).add(&GF2Element::ONE);
+        Point::Affine { x: x3, y: y3 }
+    }
+
+    /// The tangent slope is x^2 + 1, so doubling needs no inversion
+    pub fn double(&self, p: &Point) -> Point {
+        match p {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x, y } => {
+                let l = x.sqr().add(&GF2Element::ONE);
+                let x3 = l.sqr();
+                let y3 = l.mul(&x.add(&x3)).add(y).add(&GF2Element::ONE);
+                Point::Affine { x: x3, y: y3 }
+            }
+        }
+    }
+
+    /// Left-to-right double-and-add
+    pub fn mul(&self, p: &Point, k: &UnsignedLongInt) -> Point {
+        let mut out = Point::Infinity;
+        let Some(top) = k.get_highest_set_bit() else {
+            return out;
+        };
+        for i in (0..top + 1).rev() {
+            out = self.double(&out);
+            if k.get_bit(i) {
+                out = self.add(&out, p);
+            }
+        }
+        out
+    }
+
+    /// Reduced eta_T pairing, bilinear and non-degenerate on E x E, with values of order dividing #E
+    pub fn eta_t(&self, p: &Point, q: &Point) -> QuarticElement {
+        self.final_exponentiation(&self.miller_loop(p, q))
+    }
+
+    /// f_(2^K +- 1, P)(psi(Q)) up to factors from GF(2^2m), which the final exponentiation removes.
+    /// The vertical lines are such factors since x(psi(Q)) lies in GF(2^2m).
+    pub fn miller_loop(&self, p: &Point, q: &Point) -> QuarticElement {
+        let mut f = QuarticElement::from_base(GF2Element::ONE);
+        let (Point::Affine { .. }, Point::Affine { x: xq, y: yq }) = (p, q) else {
+            return f;
+        };
+
+        let mut r = p.clone();
+        for _ in 0..K {
+            let Point::Affine { x, y } = &r else {
+                unreachable!("2^i P is never the point at infinity for i < K: #E is odd");
+            };
+            f = f.sqr().mul(&line(x, y, &x.sqr().add(&GF2Element::ONE), xq, yq));
+            r = self.double(&r);
+        }
+
+        // the chord through 2^K P and nu P, a vertical line when 2^K P = -nu P
+        let s = if self.nu_is_positive() { p.clone() } else { self.neg(p) };
+        if let (Point::Affine { x: x1, y: y1 }, Point::Affine { x: x2, y: y2 }) = (&r, &s) {
+            if x1 != x2 {
+                let l = y1.add(y2).mul(&x1.add(x2).inverse());
+                f = f.mul(&line(x1, y1, &l, xq, yq));
+            } else if y1 == y2 {
+                f = f.mul(&line(x1, y1, &x1.sqr().add(&GF2Element::ONE), xq, yq));
+            }
+        }
+        f
+    }
+
+    /// f^((2^2m - 1)(2^m + 1 - nu 2^K)), where (2^4m - 1) / #E is the exponent
+    pub fn final_exponentiation(&self, f: &QuarticElement) -> QuarticElement {
+        // g^(2^2m + 1) = 1, so g^-1 = g^(2^2m) and the inversion is done once
+        let g = f.frobenius(2).mul(&f.inverse());
+        let mut g_k = g.clone();
+        for _ in 0..K {
+            g_k = g_k.sqr();
+        }
+        if self.nu_is_positive() {
+            g_k = g_k.frobenius(2);
+        }
+        g.frobenius(1).mul(&g).mul(&g_k)
+    }
+}
+
+/// psi(x, y) = (x + s^2, y + s x + t) with s^2 + s + 1 = 0 and t^2 + t = s,
+/// maps E(GF(2^m)) into E(GF(2^4m)) and out of the kernel of the trace.
+/// In GF(2^419)[z] / (z^4 + z + 1) s = z^2 + z and t = z.
+pub fn distortion_map(p: &Point) -> Option<(QuarticElement, QuarticElement)> {
+    let Point::Affine { x, y } = p else {
+        return None;
+    };
+    // s^2 = s + 1
+    let psi_x = QuarticElement::new(vec![x.add(&GF2Element::ONE), GF2Element::ONE, GF2Element::ONE, GF2Element::ZERO]);
+    let psi_y = QuarticElement::new(vec![y.clone(), x.add(&GF2Element::ONE), x.clone(), GF2Element::ZERO]);
+    Some((psi_x, psi_y))
+}
+
+// The line y + y1 + l (x + x1) through (x1, y1) with slope l evaluated at psi(Q):
+// (yq + y1 + l (xq + x1 + 1)) + (xq + l) s + t
+fn line(x1: &GF2Element<DIM>, y1: &GF2Element<DIM>, l: &GF2Element<DIM>, xq: &GF2Element<DIM>, yq: &GF2Element<DIM>) -> QuarticElement {
+    let c0 = yq.add(y1).add(&l.mul(&xq.add(x1).add(&GF2Element::ONE)));
+    let c2 = xq.add(l);
+    QuarticElement::new(vec![c0, c2.add(&GF2Element::ONE), c2, GF2Element::ZERO])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::field::BinaryField;
+    use crate::solve_sq_eq::halftrace;
+    use super::*;
+
+    // First point with x = [k; 2 * DIM] mod f(x), k >= start. Small polynomials x won't do,
+    // they give Tr(x^3 + x) = 0 and no points for b = 1
+    fn point_after(curve: &SupersingularCurve, start: usize) -> Point {
+        for k in start.. {
+            let x = GF2Element::from([k; 2 * DIM]);
+            // y^2 + y = c has a solution for Tr(c) = 0
+            let c = x.sqr().mul(&x).add(&x).add(curve.b());
+            if c.trace() == GF2Element::ZERO {
+                return Point::Affine { x, y: halftrace(&c) };
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn curve_test() -> Result<(), Box<dyn Error>> {
+        for b in [GF2Element::ZERO, GF2Element::ONE] {
+            let curve = SupersingularCurve::new(b)?;
+            let p = point_after(&curve, 2);
+            let q = point_after(&curve, 1000);
+
+            assert!(curve.is_on_curve(&curve.add(&p, &q)) && curve.is_on_curve(&curve.double(&p)));
+            assert_eq!(curve.add(&p, &curve.neg(&p)), Point::Infinity);
+            assert_eq!(curve.add(&p, &p), curve.double(&p));
+            assert_eq!(curve.mul(&p, &curve.group_order()), Point::Infinity);
+
+            // psi(Q) lies on the curve over GF(2^4m)
+            let (x, y) = distortion_map(&q).unwrap();
+            let b = QuarticElement::from_base(curve.b().clone());
+            assert_eq!(y.sqr().add(&y), x.sqr().mul(&x).add(&x).add(&b));
+        }
+        assert_eq!(SupersingularCurve::new(GF2Element::from([2; 2 * DIM])), Err(PairingError::UnsupportedCoefficient));
+
+        Ok(())
+    }
+
+    #[test]
+    fn bilinearity_test() -> Result<(), Box<dyn Error>> {
+        let one = QuarticElement::one();
+        for b in [GF2Element::ZERO, GF2Element::ONE] {
+            let curve = SupersingularCurve::new(b)?;
+            let p = point_after(&curve, 2);
+            let q = point_after(&curve, 1000);
+
+            let e = curve.eta_t(&p, &q);
+            assert_ne!(e, one);
+            assert_eq!(e.pow(&curve.group_order()), one);
+
+            let (a, c) = (UnsignedLongInt::from(5), UnsignedLongInt::from(3));
+            assert_eq!(curve.eta_t(&curve.mul(&p, &a), &curve.mul(&q, &c)), e.pow(&UnsignedLongInt::from(15)));
+            assert_eq!(curve.eta_t(&curve.add(&p, &q), &q), e.mul(&curve.eta_t(&q, &q)));
+            assert_eq!(curve.eta_t(&curve.neg(&p), &q), e.inverse());
+
+            // non-degenerate on the same point thanks to the distortion map
+            assert_ne!(curve.eta_t(&p, &p), one);
+            assert_eq!(curve.eta_t(&Point::Infinity, &q), one);
+        }
+
+        Ok(())
+    }
+}
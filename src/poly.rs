@@ -0,0 +1,221 @@
+use std::hash::{Hash, Hasher};
+use crate::field::BinaryField;
+
+/// Polynomial over a binary field, coefficients from the lowest degree without leading zeros
+#[derive(Debug, Clone, PartialEq)]
+pub struct Poly<F: BinaryField> {
+    coefs: Vec<F>,
+}
+
+impl<F: BinaryField + Eq> Eq for Poly<F> {}
+
+impl<F: BinaryField + Hash> Hash for Poly<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.coefs.hash(state);
+    }
+}
+
+impl<F: BinaryField> Poly<F> {
+    pub fn new(mut coefs: Vec<F>) -> Self {
+        while coefs.last().is_some_and(|c| c.is_zero()) {
+            coefs.pop();
+        }
+        Self { coefs }
+    }
+
+    pub fn zero() -> Self {
+        Self { coefs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        Self::constant(F::one())
+    }
+
+    pub fn constant(c: F) -> Self {
+        Self::new(vec![c])
+    }
+
+    /// c x^k
+    pub fn monomial(c: F, k: usize) -> Self {
+        let mut coefs = vec![F::zero(); k + 1];
+        coefs[k] = c;
+        Self::new(coefs)
+    }
+
+    pub fn coefs(&self) -> &[F] {
+        &self.coefs
+    }
+
+    /// Coefficient of x^i, zero above the degree
+    pub fn coef(&self, i: usize) -> F {
+        self.coefs.get(i).cloned().unwrap_or_else(F::zero)
+    }
+
+    /// None for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coefs.len().checked_sub(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefs.is_empty()
+    }
+
+    pub fn leading(&self) -> Option<&F> {
+        self.coefs.last()
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let (long, short) = if self.coefs.len() >= other.coefs.len() { (self, other) } else { (other, self) };
+        let mut coefs = long.coefs.clone();
+        for (c, s) in coefs.iter_mut().zip(short.coefs.iter()) {
+            *c = c.add(s);
+        }
+        Self::new(coefs)
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self::zero();
+        }
+        let mut coefs = vec![F::zero(); self.coefs.len() + other.coefs.len() - 1];
+        for (i, a) in self.coefs.iter().enumerate() {
+            if a.is_zero() {
+                continue;
+            }
+            for (j, b) in other.coefs.iter().enumerate() {
+                coefs[i + j] = coefs[i + j].add(&a.mul(b));
+            }
+        }
+        Self::new(coefs)
+    }
+
+    /// Only the coefficients are squared in characteristic 2
+    pub fn sqr(&self) -> Self {
+        let mut coefs = vec![F::zero(); (2 * self.coefs.len()).saturating_sub(1)];
+        for (i, a) in self.coefs.iter().enumerate() {
+            coefs[2 * i] = a.sqr();
+        }
+        Self::new(coefs)
+    }
+
+    pub fn scale(&self, c: &F) -> Self {
+        Self::new(self.coefs.iter().map(|a| a.mul(c)).collect())
+    }
+
+    /// Formal derivative, the even powers vanish
+    pub fn derivative(&self) -> Self {
+        Self::new(self.coefs.iter().enumerate().skip(1)
+            .map(|(i, a)| if i % 2 == 1 { a.clone() } else { F::zero() })
+            .collect())
+    }
+
+    pub fn eval(&self, x: &F) -> F {
+        self.coefs.iter().rev().fold(F::zero(), |acc, c| acc.mul(x).add(c))
+    }
+
+    /// Divided by the leading coefficient, zero stays zero
+    pub fn monic(&self) -> Self {
+        match self.leading() {
+            None => Self::zero(),
+            Some(lead) => self.scale(&lead.inverse()),
+        }
+    }
+
+    /// (self / d, self % d) by long division
+    pub fn div_rem(&self, d: &Self) -> (Self, Self) {
+        let d_deg = d.degree().expect("division by the zero polynomial");
+        let Some(deg) = self.degree().filter(|&deg| deg >= d_deg) else {
+            return (Self::zero(), self.clone());
+        };
+
+        let lead_inv = d.coefs[d_deg].inverse();
+        let mut r = self.coefs.clone();
+        let mut q = vec![F::zero(); deg - d_deg + 1];
+        for i in (d_deg..deg + 1).rev() {
+            if r[i].is_zero() {
+                continue;
+            }
+            let c = r[i].mul(&lead_inv);
+            for (j, b) in d.coefs.iter().enumerate() {
+                r[i - d_deg + j] = r[i - d_deg + j].add(&c.mul(b));
+            }
+            q[i - d_deg] = c;
+        }
+        r.truncate(d_deg);
+        (Self::new(q), Self::new(r))
+    }
+
+    pub fn rem(&self, d: &Self) -> Self {
+        self.div_rem(d).1
+    }
+
+    /// (g, s, t) with monic g = gcd(a, b) = s a + t b, g = 0 only for a = b = 0
+    pub fn xgcd(a: &Self, b: &Self) -> (Self, Self, Self) {
+        let (mut r0, mut r1) = (a.clone(), b.clone());
+        let (mut s0, mut s1) = (Self::one(), Self::zero());
+        let (mut t0, mut t1) = (Self::zero(), Self::one());
+        while !r1.is_zero() {
+            let (q, r) = r0.div_rem(&r1);
+            (r0, r1) = (r1, r);
+            (s0, s1) = (s1.clone(), s0.add(&q.mul(&s1)));
+            (t0, t1) = (t1.clone(), t0.add(&q.mul(&t1)));
+        }
+
+        let Some(lead) = r0.leading() else {
+            return (r0, s0, t0);
+        };
+        let lead_inv = lead.inverse();
+        (r0.scale(&lead_inv), s0.scale(&lead_inv), t0.scale(&lead_inv))
+    }
+
+    pub fn gcd(a: &Self, b: &Self) -> Self {
+        Self::xgcd(a, b).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::small_field::GF256;
+    use super::*;
+
+    fn random_poly(deg: usize) -> Poly<GF256> {
+        let mut coefs: Vec<_> = (0..deg).map(|_| GF256::new(rand::random::<u8>() as u16)).collect();
+        coefs.push(GF256::new(rand::random::<u8>().max(1) as u16));
+        Poly::new(coefs)
+    }
+
+    #[test]
+    fn arithmetic_test() {
+        for _ in 0..50 {
+            let a = random_poly(7);
+            let b = random_poly(3);
+            let c = random_poly(4);
+
+            let (q, r) = a.div_rem(&b);
+            assert_eq!(q.mul(&b).add(&r), a);
+            assert!(r.degree() < b.degree());
+            assert_eq!(a.sqr(), a.mul(&a));
+            assert_eq!(a.add(&b).mul(&c), a.mul(&c).add(&b.mul(&c)));
+            assert_eq!(a.mul(&b).eval(&GF256::new(7)), a.eval(&GF256::new(7)).mul(&b.eval(&GF256::new(7))));
+            assert_eq!(a.mul(&b).derivative(), a.derivative().mul(&b).add(&a.mul(&b.derivative())));
+        }
+        assert_eq!(Poly::new(vec![GF256::ONE, GF256::ZERO]).degree(), Some(0));
+        assert_eq!(random_poly(2).add(&Poly::zero()).degree(), Some(2));
+    }
+
+    #[test]
+    fn xgcd_test() {
+        for _ in 0..50 {
+            let g = random_poly(2).monic();
+            let a = random_poly(5).mul(&g);
+            let b = random_poly(4).mul(&g);
+
+            let (d, s, t) = Poly::xgcd(&a, &b);
+            assert_eq!(s.mul(&a).add(&t.mul(&b)), d);
+            assert!(a.rem(&d).is_zero() && b.rem(&d).is_zero());
+            assert!(d.rem(&g).is_zero());
+            assert_eq!(d.leading(), Some(&GF256::ONE));
+        }
+        assert_eq!(Poly::<GF256>::gcd(&Poly::zero(), &Poly::zero()), Poly::zero());
+    }
+}
@@ -0,0 +1,202 @@
+use vl_big_ints::UnsignedLongInt;
+use crate::curve::{Curve, Point};
+use crate::field::BinaryField;
+use crate::lopez_dahab::LdPoint;
+
+/// Width-w NAF of k, least significant digit first. Non-zero digits are odd, below 2^(w-1)
+/// in absolute value and followed by at least w - 1 zeros.
+pub fn wnaf(k: &UnsignedLongInt, w: usize) -> Vec<i32> {
+    assert!((2..=16).contains(&w), "window width must be between 2 and 16");
+    let Some(top) = k.get_highest_set_bit() else {
+        return Vec::new();
+    };
+
+    // room for the carries of negative digits
+    let mut bits: Vec<bool> = (0..top + w + 2).map(|i| i <= top && k.get_bit(i)).collect();
+    let mut digits = Vec::with_capacity(bits.len());
+
+    for i in 0..bits.len() - w {
+        if !bits[i] {
+            digits.push(0);
+            continue;
+        }
+
+        // k mods 2^w, then k - d has w zero bits at the bottom
+        let v = (0..w).filter(|&j| bits[i + j]).fold(0i32, |acc, j| acc | 1 << j);
+        let d = if v >= 1 << (w - 1) { v - (1 << w) } else { v };
+        digits.push(d);
+
+        bits[i..i + w].iter_mut().for_each(|b| *b = false);
+        if d < 0 {
+            let mut j = i + w;
+            while bits[j] {
+                bits[j] = false;
+                j += 1;
+            }
+            bits[j] = true;
+        }
+    }
+
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    digits
+}
+
+impl<F: BinaryField> Curve<F> {
+    /// kP with the width-w NAF of k and the odd multiples P, 3P, ..., (2^(w-1) - 1)P
+    pub fn wnaf_mul(&self, p: &Point<F>, k: &UnsignedLongInt, w: usize) -> Point<F> {
+        let digits = wnaf(k, w);
+        let odd = self.odd_multiples(p, 1 << (w - 2));
+
+        let mut out = LdPoint::infinity();
+        for d in digits.iter().rev() {
+            out = self.ld_double(&out);
+            if *d > 0 {
+                out = self.ld_add_mixed(&out, &odd[(*d as usize - 1) / 2]);
+            } else if *d < 0 {
+                out = self.ld_add_mixed(&out, &self.neg(&odd[(d.unsigned_abs() as usize - 1) / 2]));
+            }
+        }
+        self.to_affine(&out)
+    }
+
+    // P, 3P, 5P, ... in affine coordinates
+    fn odd_multiples(&self, p: &Point<F>, count: usize) -> Vec<Point<F>> {
+        let double = self.ld_double(&LdPoint::from(p));
+        let mut multiples = vec![LdPoint::from(p)];
+        for i in 1..count {
+            multiples.push(self.ld_add(&multiples[i - 1], &double));
+        }
+        self.to_affine_batch(&multiples)
+    }
+}
+
+/// Lim-Lee comb for a fixed base point. The scalar bits are split into `window` rows of `spacing` bits,
+/// the table holds every sum of 2^(r spacing) P over subsets of rows.
+#[derive(Debug, Clone)]
+pub struct FixedBaseComb {
+    curve: Curve,
+    window: usize,
+    spacing: usize,
+    table: Vec<Point>,
+}
+
+impl FixedBaseComb {
+    /// Table of 2^window points for scalars below 2^bits
+    pub fn new(curve: &Curve, p: &Point, window: usize, bits: usize) -> Self {
+        assert!((1..=16).contains(&window), "comb window must be between 1 and 16");
+        let spacing = bits.div_ceil(window).max(1);
+
+        // rows[r] = 2^(r spacing) P
+        let mut rows = vec![LdPoint::from(p)];
+        for r in 1..window {
+            let mut row = rows[r - 1].clone();
+            for _ in 0..spacing {
+                row = curve.ld_double(&row);
+            }
+            rows.push(row);
+        }
+
+        let mut table = vec![LdPoint::infinity()];
+        for (r, row) in rows.iter().enumerate() {
+            for j in 0..1 << r {
+                table.push(curve.ld_add(&table[j], row));
+            }
+        }
+
+        Self { curve: curve.clone(), window, spacing, table: curve.to_affine_batch(&table) }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Largest supported scalar bit length
+    pub fn bits(&self) -> usize {
+        self.window * self.spacing
+    }
+
+    pub fn table_size(&self) -> usize {
+        self.table.len()
+    }
+
+    /// kP in `spacing` doublings and at most as many mixed additions
+    pub fn mul(&self, k: &UnsignedLongInt) -> Point {
+        if let Some(top) = k.get_highest_set_bit() {
+            assert!(top < self.bits(), "scalar is longer than the comb table supports");
+        }
+
+        let mut out = LdPoint::infinity();
+        for i in (0..self.spacing).rev() {
+            out = self.curve.ld_double(&out);
+            let index = (0..self.window)
+                .filter(|r| k.get_bit(r * self.spacing + i))
+                .fold(0, |acc, r| acc | 1 << r);
+            if index != 0 {
+                out = self.curve.ld_add_mixed(&out, &self.table[index]);
+            }
+        }
+        self.curve.to_affine(&out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::curve::tests::{point_after, test_curve};
+    use crate::MOD_DEG;
+    use super::*;
+
+    #[test]
+    fn wnaf_test() {
+        for w in 2..7 {
+            let k = rand::random::<u64>();
+            let digits = wnaf(&UnsignedLongInt::from(k), w);
+
+            let value = digits.iter().rev().fold(0i128, |acc, d| 2 * acc + *d as i128);
+            assert_eq!(value, k as i128);
+            for (i, d) in digits.iter().enumerate().filter(|(_, d)| **d != 0) {
+                assert_eq!(d.rem_euclid(2), 1);
+                assert!(d.abs() < 1 << (w - 1));
+                assert!(digits[i + 1..].iter().take(w - 1).all(|d| *d == 0));
+            }
+        }
+        assert!(wnaf(&UnsignedLongInt::from(0), 4).is_empty());
+        assert_eq!(wnaf(&UnsignedLongInt::from(7), 2), vec![-1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn wnaf_mul_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let p = point_after(&curve, 2);
+        let k = UnsignedLongInt::from(rand::random::<u64>());
+
+        let expected = curve.ld_mul(&p, &k);
+        for w in [2, 4, 5] {
+            assert_eq!(curve.wnaf_mul(&p, &k, w), expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn comb_test() -> Result<(), Box<dyn Error>> {
+        let curve = test_curve()?;
+        let p = point_after(&curve, 2);
+
+        let comb = FixedBaseComb::new(&curve, &p, 4, 64);
+        assert_eq!(comb.table_size(), 16);
+        for k in [0, 1, u64::MAX, rand::random::<u64>()] {
+            let k = UnsignedLongInt::from(k);
+            assert_eq!(comb.mul(&k), curve.ld_mul(&p, &k));
+        }
+
+        // full length scalars
+        let comb = FixedBaseComb::new(&curve, &p, 6, MOD_DEG + 1);
+        let k = UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(MOD_DEG as u64)).sub(&UnsignedLongInt::from(rand::random::<u64>()));
+        assert_eq!(comb.mul(&k), curve.wnaf_mul(&p, &k, 5));
+
+        Ok(())
+    }
+}
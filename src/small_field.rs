@@ -0,0 +1,345 @@
+use std::fmt::{Debug, Display, Formatter, LowerHex};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use rand::RngCore;
+use vl_big_ints::UnsignedLongInt;
+use crate::field::{BinaryField, PolynomialBasis, RandomElement};
+use crate::from_str::GF2ElementFromStrError;
+
+/// Parameters of a binary field small enough for exp/log tables
+pub trait SmallFieldParams: Debug + Clone + Copy + PartialEq + Eq + Hash {
+    const BITS: u32;
+    /// Irreducible polynomial including the leading term
+    const MODULUS: u32;
+    /// Primitive element used as the base of the log table
+    const GENERATOR: u16;
+
+    fn tables() -> &'static ExpLogTables;
+}
+
+/// exp[i] = g^i for i < 2(q - 1), so a product never needs a reduction of the log sum;
+/// log[a] = i with g^i = a for a != 0
+#[derive(Debug)]
+pub struct ExpLogTables {
+    exp: Vec<u16>,
+    log: Vec<u16>,
+}
+
+impl ExpLogTables {
+    fn build<P: SmallFieldParams>() -> Self {
+        let q = 1usize << P::BITS;
+        let mut exp = vec![0u16; 2 * (q - 1)];
+        let mut log = vec![0u16; q];
+
+        let mut a: u16 = 1;
+        for i in 0..q - 1 {
+            assert!(i == 0 || a != 1, "generator is not primitive");
+            exp[i] = a;
+            exp[i + q - 1] = a;
+            log[a as usize] = i as u16;
+            a = mul_slow(a, P::GENERATOR, P::BITS, P::MODULUS);
+        }
+
+        Self { exp, log }
+    }
+}
+
+/// GF(2^8) with the AES polynomial x^8 + x^4 + x^3 + x + 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Aes;
+
+impl SmallFieldParams for Aes {
+    const BITS: u32 = 8;
+    const MODULUS: u32 = 0x11B;
+    const GENERATOR: u16 = 0x03;
+
+    fn tables() -> &'static ExpLogTables {
+        static TABLES: OnceLock<ExpLogTables> = OnceLock::new();
+        TABLES.get_or_init(ExpLogTables::build::<Self>)
+    }
+}
+
+/// GF(2^16) with the primitive polynomial x^16 + x^5 + x^3 + x^2 + 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gf16;
+
+impl SmallFieldParams for Gf16 {
+    const BITS: u32 = 16;
+    const MODULUS: u32 = 0x1002D;
+    const GENERATOR: u16 = 0x02;
+
+    fn tables() -> &'static ExpLogTables {
+        static TABLES: OnceLock<ExpLogTables> = OnceLock::new();
+        TABLES.get_or_init(ExpLogTables::build::<Self>)
+    }
+}
+
+pub type GF256 = SmallField<Aes>;
+pub type GF65536 = SmallField<Gf16>;
+
+/// Element of a small binary field, the value is the polynomial with bit i at x^i
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SmallField<P: SmallFieldParams> {
+    value: u16,
+    params: PhantomData<P>,
+}
+
+impl<P: SmallFieldParams> SmallField<P> {
+    pub const ZERO: Self = Self { value: 0, params: PhantomData };
+    pub const ONE: Self = Self { value: 1, params: PhantomData };
+
+    pub fn new(value: u16) -> Self {
+        assert!((value as u32) < 1 << P::BITS, "value doesn't fit into the field");
+        Self { value, params: PhantomData }
+    }
+
+    pub fn generator() -> Self {
+        Self::new(P::GENERATOR)
+    }
+
+    pub fn value(&self) -> u16 {
+        self.value
+    }
+
+    fn order() -> usize {
+        (1 << P::BITS) - 1
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        Self::new(self.value ^ other.value)
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        if self.value == 0 || other.value == 0 {
+            return Self::ZERO;
+        }
+        let t = P::tables();
+        Self::new(t.exp[t.log[self.value as usize] as usize + t.log[other.value as usize] as usize])
+    }
+
+    pub fn sqr(&self) -> Self {
+        self.mul(self)
+    }
+
+    pub fn pow(&self, e: &UnsignedLongInt) -> Self {
+        // exponents only matter modulo the group order
+        let n = Self::order() as u64;
+        let e = match e.get_highest_set_bit() {
+            None => return Self::ONE,
+            Some(top) => (0..top + 1).rev().fold(0, |acc, i| (2 * acc + e.get_bit(i) as u64) % n),
+        };
+        if self.value == 0 {
+            return Self::ZERO;
+        }
+
+        let t = P::tables();
+        Self::new(t.exp[(t.log[self.value as usize] as u64 * e % n) as usize])
+    }
+
+    /// Zero is mapped to zero, like a^(q-2) would do
+    pub fn inverse(&self) -> Self {
+        if self.value == 0 {
+            return Self::ZERO;
+        }
+        let t = P::tables();
+        Self::new(t.exp[Self::order() - t.log[self.value as usize] as usize])
+    }
+
+    pub fn trace(&self) -> Self {
+        let mut s = *self;
+        let mut out = *self;
+        for _ in 1..P::BITS {
+            s = s.sqr();
+            out = out.add(&s);
+        }
+        out
+    }
+
+    /// Discrete logarithm to the base `generator()`
+    pub fn log(&self) -> Option<u16> {
+        if self.value == 0 {
+            None
+        } else {
+            Some(P::tables().log[self.value as usize])
+        }
+    }
+}
+
+impl<P: SmallFieldParams> BinaryField for SmallField<P> {
+    const DEGREE: usize = P::BITS as usize;
+
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        SmallField::add(self, other)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        SmallField::mul(self, other)
+    }
+
+    fn sqr(&self) -> Self {
+        SmallField::sqr(self)
+    }
+
+    fn pow(&self, e: &UnsignedLongInt) -> Self {
+        SmallField::pow(self, e)
+    }
+
+    fn inverse(&self) -> Self {
+        SmallField::inverse(self)
+    }
+
+    fn trace(&self) -> Self {
+        SmallField::trace(self)
+    }
+}
+
+impl<P: SmallFieldParams> PolynomialBasis for SmallField<P> {
+    fn modulus() -> Vec<usize> {
+        (0..=P::BITS).rev().filter(|i| P::MODULUS & (1 << i) != 0).map(|i| i as usize).collect()
+    }
+
+    fn to_words(&self) -> Vec<u64> {
+        vec![self.value as u64]
+    }
+
+    fn from_words(words: &[u64]) -> Option<Self> {
+        let (low, high) = words.split_first().unwrap_or((&0, &[]));
+        (*low < 1 << P::BITS && high.iter().all(|w| *w == 0)).then(|| Self::new(*low as u16))
+    }
+}
+
+impl<P: SmallFieldParams> RandomElement for SmallField<P> {
+    fn random<R: RngCore>(rng: &mut R) -> Self {
+        SmallField::new((rng.next_u32() & ((1 << P::BITS) - 1)) as u16)
+    }
+}
+
+impl<P: SmallFieldParams> FromStr for SmallField<P> {
+    type Err = GF2ElementFromStrError;
+    // for hex strings
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = u32::from_str_radix(s, 16)?;
+        if value >= 1 << P::BITS {
+            return Err(GF2ElementFromStrError::NotAnElementError);
+        }
+
+        Ok(Self::new(value as u16))
+    }
+}
+
+impl<P: SmallFieldParams> Display for SmallField<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for pow in (1..P::BITS).rev() {
+            if self.value & (1 << pow) != 0 {
+                write!(f, "x^{pow} + ")?;
+            }
+        }
+        write!(f, "{}]", self.value & 1)
+    }
+}
+
+impl<P: SmallFieldParams> LowerHex for SmallField<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:0width$x}", self.value, width = (P::BITS / 4) as usize)
+    }
+}
+
+// Shift-and-add multiplication, only used to fill the tables
+fn mul_slow(a: u16, b: u16, bits: u32, modulus: u32) -> u16 {
+    let mut a = a as u32;
+    let mut b = b;
+    let mut r = 0u32;
+    while b != 0 {
+        if b & 1 == 1 {
+            r ^= a;
+        }
+        b >>= 1;
+        a <<= 1;
+        if a & (1 << bits) != 0 {
+            a ^= modulus;
+        }
+    }
+    r as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::dlog::pohlig_hellman;
+    use crate::matrix::Matrix;
+    use crate::order::known_factorization;
+    use super::*;
+
+    #[test]
+    fn aes_test() -> Result<(), Box<dyn Error>> {
+        // FIPS 197, section 4.2 and 4.2.1
+        let a = GF256::from_str("57")?;
+        assert_eq!(a.mul(&GF256::from_str("83")?), GF256::from_str("c1")?);
+        assert_eq!(GF256::from_str("53")?.inverse(), GF256::from_str("ca")?);
+
+        for v in 0..256 {
+            let x = GF256::new(v);
+            assert_eq!(x.mul(&a).value(), mul_slow(v, 0x57, 8, 0x11B));
+        }
+        assert_eq!(format!("{}", a), "[x^6 + x^4 + x^2 + x^1 + 1]");
+        assert_eq!(format!("{:x}", a), "57");
+        assert_eq!(GF256::from_str("100"), Err(GF2ElementFromStrError::NotAnElementError));
+        assert_eq!(GF256::modulus(), [8, 4, 3, 1, 0]);
+        assert_eq!(GF256::from_words(&a.to_words()), Some(a));
+        assert_eq!(GF256::from_words(&[0x100]), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn arithmetic_test() {
+        let mut ones = 0;
+        for v in 1..=u16::MAX {
+            let x = GF65536::new(v);
+            assert_eq!(x.mul(&x.inverse()), GF65536::ONE);
+            if x.trace() == GF65536::ONE {
+                ones += 1;
+            }
+        }
+        // trace is balanced
+        assert_eq!(ones, 1 << 15);
+
+        let x = GF65536::new(0xBEEF);
+        let e = UnsignedLongInt::from(1_000_003);
+        let mut expected = GF65536::ONE;
+        for _ in 0..1_000_003 % 65535 {
+            expected = expected.mul(&x);
+        }
+        assert_eq!(x.pow(&e), expected);
+        assert_eq!(GF65536::generator().pow(&UnsignedLongInt::from(65535)), GF65536::ONE);
+    }
+
+    #[test]
+    fn generic_algorithms_test() -> Result<(), Box<dyn Error>> {
+        let m = Matrix::from_rows(vec![
+            vec![GF256::new(0x02), GF256::new(0x03), GF256::new(0x01)],
+            vec![GF256::new(0x01), GF256::new(0x02), GF256::new(0x03)],
+            vec![GF256::new(0x01), GF256::new(0x01), GF256::new(0x02)],
+        ])?;
+        assert_eq!(m.mul(&m.inverse()?)?, Matrix::identity(3));
+
+        // 2^16 - 1 = 3 * 5 * 17 * 257
+        let g = GF65536::generator();
+        let h = GF65536::new(0x1234);
+        let x = pohlig_hellman(&g, &h, &known_factorization(16)?)?;
+        assert_eq!(x, UnsignedLongInt::from(h.log().unwrap() as u64));
+
+        Ok(())
+    }
+}
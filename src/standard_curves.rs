@@ -0,0 +1,484 @@
+use std::str::FromStr;
+use vl_big_ints::UnsignedLongInt;
+use crate::curve::{Curve, CurveError, Point};
+use crate::domain::DomainParameters;
+use crate::field::PolynomialBasis;
+use crate::gf2m::{
+    Dstu167, Dstu173, Dstu179, Dstu191, Dstu233, Dstu257, Dstu307, Dstu367, Dstu431, Gf2m,
+    Sect113, Sect131, Sect163, Sect193, Sect233, Sect239, Sect283, Sect409, Sect571,
+};
+use crate::validation::{check_order, is_irreducible, validate_domain_parameters, ValidationError};
+use crate::{GF2Element, DIM};
+
+/// Published binary curve y^2 + xy = x^3 + a x^2 + b with a base point G of prime order n,
+/// all field elements and n are big-endian hex strings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandardCurve {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    /// Exponents of the field modulus, the highest first
+    pub modulus: &'static [usize],
+    pub a: &'static str,
+    pub b: &'static str,
+    pub gx: &'static str,
+    pub gy: &'static str,
+    pub order: &'static str,
+    pub cofactor: u64,
+}
+
+// FIPS 186-4 D.1.3 (the same curves are sect*k1 and sect*r1 in SEC 2), the other binary curves
+// of SEC 2 and the recommended curves of DSTU 4145-2002. DSTU 4145 fixes only a, b and n, every
+// user picks a base point. The 163-bit curve has the one of the annex example, the others
+// h R for the point R with the smallest x >= 2 (x read as a polynomial) and y = x z with
+// z the half trace of x + a + b / x^2.
+// None of them is over GF(2^419), their fields are the Gf2m types listed in `validate`.
+pub const STANDARD_CURVES: &[StandardCurve] = &[
+    StandardCurve {
+        name: "K-163",
+        aliases: &["sect163k1"],
+        modulus: &[163, 7, 6, 3, 0],
+        a: "1",
+        b: "1",
+        gx: "2FE13C0537BBC11ACAA07D793DE4E6D5E5C94EEE8",
+        gy: "289070FB05D38FF58321F2E800536D538CCDAA3D9",
+        order: "4000000000000000000020108A2E0CC0D99F8A5EF",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "B-163",
+        aliases: &["sect163r2"],
+        modulus: &[163, 7, 6, 3, 0],
+        a: "1",
+        b: "20A601907B8C953CA1481EB10512F78744A3205FD",
+        gx: "3F0EBA16286A2D57EA0991168D4994637E8343E36",
+        gy: "0D51FBC6C71A0094FA2CDD545B11C5C0C797324F1",
+        order: "40000000000000000000292FE77E70C12A4234C33",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "K-233",
+        aliases: &["sect233k1"],
+        modulus: &[233, 74, 0],
+        a: "0",
+        b: "1",
+        gx: "17232BA853A7E731AF129F22FF4149563A419C26BF50A4C9D6EEFAD6126",
+        gy: "1DB537DECE819B7F70F555A67C427A8CD9BF18AEB9B56E0C11056FAE6A3",
+        order: "8000000000000000000000000000069D5BB915BCD46EFB1AD5F173ABDF",
+        cofactor: 4,
+    },
+    StandardCurve {
+        name: "B-233",
+        aliases: &["sect233r1"],
+        modulus: &[233, 74, 0],
+        a: "1",
+        b: "066647EDE6C332C7F8C0923BB58213B333B20E9CE4281FE115F7D8F90AD",
+        gx: "0FAC9DFCBAC8313BB2139F1BB755FEF65BC391F8B36F8F8EB7371FD558B",
+        gy: "1006A08A41903350678E58528BEBF8A0BEFF867A7CA36716F7E01F81052",
+        order: "1000000000000000000000000000013E974E72F8A6922031D2603CFE0D7",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "K-283",
+        aliases: &["sect283k1"],
+        modulus: &[283, 12, 7, 5, 0],
+        a: "0",
+        b: "1",
+        gx: "503213F78CA44883F1A3B8162F188E553CD265F23C1567A16876913B0C2AC2458492836",
+        gy: "1CCDA380F1C9E318D90F95D07E5426FE87E45C0E8184698E45962364E34116177DD2259",
+        order: "1FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFE9AE2ED07577265DFF7F94451E061E163C61",
+        cofactor: 4,
+    },
+    StandardCurve {
+        name: "B-283",
+        aliases: &["sect283r1"],
+        modulus: &[283, 12, 7, 5, 0],
+        a: "1",
+        b: "27B680AC8B8596DA5A4AF8A19A0303FCA97FD7645309FA2A581485AF6263E313B79A2F5",
+        gx: "5F939258DB7DD90E1934F8C70B0DFEC2EED25B8557EAC9C80E2E198F8CDBECD86B12053",
+        gy: "3676854FE24141CB98FE6D4B20D02B4516FF702350EDDB0826779C813F0DF45BE8112F4",
+        order: "3FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEF90399660FC938A90165B042A7CEFADB307",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "K-409",
+        aliases: &["sect409k1"],
+        modulus: &[409, 87, 0],
+        a: "0",
+        b: "1",
+        gx: "060F05F658F49C1AD3AB1890F7184210EFD0987E307C84C27ACCFB8F9F67CC2C460189EB5AAAA62EE222EB1B35540CFE9023746",
+        gy: "1E369050B7C4E42ACBA1DACBF04299C3460782F918EA427E6325165E9EA10E3DA5F6C42E9C55215AA9CA27A5863EC48D8E0286B",
+        order: "7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFE5F83B2D4EA20400EC4557D5ED3E3E7CA5B4B5C83B8E01E5FCF",
+        cofactor: 4,
+    },
+    StandardCurve {
+        name: "B-409",
+        aliases: &["sect409r1"],
+        modulus: &[409, 87, 0],
+        a: "1",
+        b: "021A5C2C8EE9FEB5C4B9A753B7B476B7FD6422EF1F3DD674761FA99D6AC27C8A9A197B272822F6CD57A55AA4F50AE317B13545F",
+        gx: "15D4860D088DDB3496B0C6064756260441CDE4AF1771D4DB01FFE5B34E59703DC255A868A1180515603AEAB60794E54BB7996A7",
+        gy: "061B1CFAB6BE5F32BBFA78324ED106A7636B9C5A7BD198D0158AA4F5488D08F38514F1FDF4B4F40D2181B3681C364BA0273C706",
+        order: "10000000000000000000000000000000000000000000000000001E2AAD6A612F33307BE5FA47C3C9E052F838164CD37D9A21173",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "K-571",
+        aliases: &["sect571k1"],
+        modulus: &[571, 10, 5, 2, 0],
+        a: "0",
+        b: "1",
+        gx: "26EB7A859923FBC82189631F8103FE4AC9CA2970012D5D46024804801841CA44370958493B205E647DA304DB4CEB08CBBD1BA39494776FB988B47174DCA88C7E2945283A01C8972",
+        gy: "349DC807F4FBF374F4AEADE3BCA95314DD58CEC9F307A54FFC61EFC006D8A2C9D4979C0AC44AEA74FBEBBB9F772AEDCB620B01A7BA7AF1B320430C8591984F601CD4C143EF1C7A3",
+        order: "020000000000000000000000000000000000000000000000000000000000000000000000131850E1F19A63E4B391A8DB917F4138B630D84BE5D639381E91DEB45CFE778F637C1001",
+        cofactor: 4,
+    },
+    StandardCurve {
+        name: "B-571",
+        aliases: &["sect571r1"],
+        modulus: &[571, 10, 5, 2, 0],
+        a: "1",
+        b: "2F40E7E2221F295DE297117B7F3D62F5C6A97FFCB8CEFF1CD6BA8CE4A9A18AD84FFABBD8EFA59332BE7AD6756A66E294AFD185A78FF12AA520E4DE739BACA0C7FFEFF7F2955727A",
+        gx: "303001D34B856296C16C0D40D3CD7750A93D1D2955FA80AA5F40FC8DB7B2ABDBDE53950F4C0D293CDD711A35B67FB1499AE60038614F1394ABFA3B4C850D927E1E7769C8EEC2D19",
+        gy: "37BF27342DA639B6DCCFFFEB73D69D78C6C27A6009CBBCA1980F8533921E8A684423E43BAB08A576291AF8F461BB2A8B3531D2F0485C19B16E2F1516E23DD3C1A4827AF1B8AC15B",
+        order: "3FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFE661CE18FF55987308059B186823851EC7DD9CA1161DE93D5174D66E8382E9BB2FE84E47",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "sect113r1",
+        aliases: &[],
+        modulus: &[113, 9, 0],
+        a: "03088250CA6E7C7FE649CE85820F7",
+        b: "0E8BEE4D3E2260744188BE0E9C723",
+        gx: "09D73616F35F4AB1407D73562C10F",
+        gy: "0A52830277958EE84D1315ED31886",
+        order: "100000000000000D9CCEC8A39E56F",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "sect113r2",
+        aliases: &[],
+        modulus: &[113, 9, 0],
+        a: "0689918DBEC7E5A0DD6DFC0AA55C7",
+        b: "095E9A9EC9B297BD4BF36E059184F",
+        gx: "1A57A6A7B26CA5EF52FCDB8164797",
+        gy: "0B3ADC94ED1FE674C06E695BABA1D",
+        order: "10000000000000108789B2496AF93",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "sect131r1",
+        aliases: &[],
+        modulus: &[131, 8, 3, 2, 0],
+        a: "7A11B09A76B562144418FF3FF8C2570B8",
+        b: "217C05610884B63B9C6C7291678F9D341",
+        gx: "081BAF91FDF9833C40F9C181343638399",
+        gy: "78C6E7EA38C001F73C8134B1B4EF9E150",
+        order: "400000000000000023123953A9464B54D",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "sect131r2",
+        aliases: &[],
+        modulus: &[131, 8, 3, 2, 0],
+        a: "3E5A88919D7CAFCBF415F07C2176573B2",
+        b: "4B8266A46C55657AC734CE38F018F2192",
+        gx: "356DCD8F2F95031AD652D23951BB366A8",
+        gy: "648F06D867940A5366D9E265DE9EB240F",
+        order: "400000000000000016954A233049BA98F",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "sect163r1",
+        aliases: &[],
+        modulus: &[163, 7, 6, 3, 0],
+        a: "7B6882CAAEFA84F9554FF8428BD88E246D2782AE2",
+        b: "713612DCDDCB40AAB946BDA29CA91F73AF958AFD9",
+        gx: "369979697AB43897789566789567F787A7876A654",
+        gy: "0435EDB42EFAFB2989D51FEFCE3C80988F41FF883",
+        order: "3FFFFFFFFFFFFFFFFFFFF48AAB689C29CA710279B",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "sect193r1",
+        aliases: &[],
+        modulus: &[193, 15, 0],
+        a: "017858FEB7A98975169E171F77B4087DE098AC8A911DF7B01",
+        b: "0FDFB49BFE6C3A89FACADAA7A1E5BBC7CC1C2E5D831478814",
+        gx: "1F481BC5F0FF84A74AD6CDF6FDEF4BF6179625372D8C0C5E1",
+        gy: "025E399F2903712CCF3EA9E3A1AD17FB0B3201B6AF7CE1B05",
+        order: "1000000000000000000000000C7F34A778F443ACC920EBA49",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "sect193r2",
+        aliases: &[],
+        modulus: &[193, 15, 0],
+        a: "163F35A5137C2CE3EA6ED8667190B0BC43ECD69977702709B",
+        b: "0C9BB9E8927D4D64C377E2AB2856A5B16E3EFB7F61D4316AE",
+        gx: "0D9B67D192E0367C803F39E1A7E82CA14A651350AAE617E8F",
+        gy: "1CE94335607C304AC29E7DEFBD9CA01F596F927224CDECF6C",
+        order: "10000000000000000000000015AAB561B005413CCD4EE99D5",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "sect239k1",
+        aliases: &[],
+        modulus: &[239, 158, 0],
+        a: "0",
+        b: "1",
+        gx: "29A0B6A887A983E9730988A68727A8B2D126C44CC2CC7B2A6555193035DC",
+        gy: "76310804F12E549BDB011C103089E73510ACB275FC312A5DC6B76553F0CA",
+        order: "2000000000000000000000000000005A79FEC67CB6E91F1C1DA800E478A5",
+        cofactor: 4,
+    },
+    StandardCurve {
+        name: "DSTU 4145 M163",
+        aliases: &[],
+        modulus: &[163, 7, 6, 3, 0],
+        a: "1",
+        b: "5FF6108462A2DC8210AB403925E638A19C1455D21",
+        gx: "72D867F93A93AC27DF9FF01AFFE74885C8C540420",
+        gy: "0224A9C3947852B97C5599D5F4AB81122ADC3FD9B",
+        order: "400000000000000000002BEC12BE2262D39BCF14D",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "DSTU 4145 M167",
+        aliases: &[],
+        modulus: &[167, 6, 0],
+        a: "1",
+        b: "6EE3CEEB230811759F20518A0930F1A4315A827DAC",
+        gx: "1C9F3C9DA0FD504ED30ABAD757A565215AEDD5E6D9",
+        gy: "24453EB3D0AF1A68DE4A053BEE52F1C6E494584A49",
+        order: "3FFFFFFFFFFFFFFFFFFFFFB12EBCC7D7F29FF7701F",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "DSTU 4145 M173",
+        aliases: &[],
+        modulus: &[173, 10, 2, 1, 0],
+        a: "0",
+        b: "108576C80499DB2FC16EDDF6853BBB278F6B6FB437D9",
+        gx: "07D4EDAB7CC9E5C18400E48A0E195274FDDB655276FC",
+        gy: "03EFDB28EDE423343D3F7A7F4483D241FD882C69BA1D",
+        order: "0800000000000000000000189B4E67606E3825BB2831",
+        cofactor: 4,
+    },
+    StandardCurve {
+        name: "DSTU 4145 M179",
+        aliases: &[],
+        modulus: &[179, 4, 2, 1, 0],
+        a: "1",
+        b: "4A6E0856526436F2F88DD07A341E32D04184572BEB710",
+        gx: "421CA811EF4BF1CF67D6C54DF1535A3ABF8144F7371A3",
+        gy: "1794D38B9CCE9CC1EB87CC398C8C3D792E7A643FA1D10",
+        order: "3FFFFFFFFFFFFFFFFFFFFFFB981960435FE5AB64236EF",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "DSTU 4145 M191",
+        aliases: &[],
+        modulus: &[191, 9, 0],
+        a: "1",
+        b: "7BC86E2102902EC4D5890E8B6B4981FF27E0482750FEFC03",
+        gx: "3365F8D7EA8BFD8FA44A158A3890B54CC2CD50A86EA673C1",
+        gy: "1AFA71BE606CB2E6F46B543A0F7C19BC69C5D594411E4113",
+        order: "40000000000000000000000069A779CAC1DABC6788F7474F",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "DSTU 4145 M233",
+        aliases: &[],
+        modulus: &[233, 9, 4, 1, 0],
+        a: "1",
+        b: "06973B15095675534C7CF7E64A21BD54EF5DD3B8A0326AA936ECE454D2C",
+        gx: "01A5CEC542559D54D31F3DF992886F553BD774EE280C9AAA4DBB391534F",
+        gy: "1923DBF7B8A0115E01A7A223CF10805AED21CFFBBDFBB796AE3C40FDFF9",
+        order: "1000000000000000000000000000013E974E72F8A6922031D2603CFE0D7",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "DSTU 4145 M257",
+        aliases: &[],
+        modulus: &[257, 12, 0],
+        a: "0",
+        b: "1CEF494720115657E18F938D7A7942394FF9425C1458C57861F9EEA6ADBE3BE10",
+        gx: "16D27EA21187AA1D1FD00C37D4C259B4ED67E9B01B7A935AE9169ADDAF04896FF",
+        gy: "012C40D42E17631B50EC61C3FB4D887C81787B76E572E25E53B6AE6E3B17F9051",
+        order: "0800000000000000000000000000000006759213AF182E987D3E17714907D470D",
+        cofactor: 4,
+    },
+    StandardCurve {
+        name: "DSTU 4145 M307",
+        aliases: &[],
+        modulus: &[307, 8, 4, 2, 0],
+        a: "1",
+        b: "393C7F7D53666B5054B5E6C6D3DE94F4296C0C599E2E2E241050DF18B6090BDC90186904968BB",
+        gx: "6E4F1FDF54D99AD4152D79B1B4F7A53D0A5B0316678B8B89041437C62D8242F724061A4125AE5",
+        gy: "464C33528898E7877AAD947DBBB5CC1C6BD7E23AACF41DFE1C3BA4EE5EAB1CFD047D6A74A7B1D",
+        order: "3FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFC079C2F3825DA70D390FBBA588D4604022B7B7",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "DSTU 4145 M367",
+        aliases: &[],
+        modulus: &[367, 21, 0],
+        a: "1",
+        b: "43FC8AD242B0B7A6F3D1627AD5654447556B47BF6AA4A64B0C2AFE42CADAB8F93D92394C79A79755437B56995136",
+        gx: "50FF22B490AC2DE9BCF4589EB5595111D55AD1EFDAA92992C30ABF90B2B6AE3E4F648E531E69E5D550DED5B65449",
+        gy: "04F561A1C1F8968200A4F78B2CE7B492A33B936AA8F2D02067FCA69260193CB8C158BE2BD6B49FD0463B4BD7C8CB",
+        order: "40000000000000000000000000000000000000000000009C300B75A3FA824F22428FD28CE8812245EF44049B2D49",
+        cofactor: 2,
+    },
+    StandardCurve {
+        name: "DSTU 4145 M431",
+        aliases: &[],
+        modulus: &[431, 5, 3, 1, 0],
+        a: "1",
+        b: "03CE10490F6A708FC26DFE8C3D27C4F94E690134D5BFF988D8D28AAEAEDE975936C66BAC536B18AE2DC312CA493117DAA469C640CAF3",
+        gx: "3B84ABBF669F522A59BD0F1956B9E26904AC55473EB0F09194C13B1B5F1C2BC90359F9284124FE6C20D1231D73A989E3937184266A13",
+        gy: "5B9F6F525540C7421673FDE6A74BAEECB6D58199666BAD8390B6706800C77BC4A1C57A8A4BC45ED57E2286A3948A404D3AF66C3C3667",
+        order: "3FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFBA3175458009A8C0A724F02F81AA8A1FCBAF80D90C7A95110504CF",
+        cofactor: 2,
+    },
+];
+
+/// Looks a curve up by its name or one of its aliases
+pub fn standard_curve(name: &str) -> Option<&'static StandardCurve> {
+    STANDARD_CURVES.iter().find(|c| c.name == name || c.aliases.contains(&name))
+}
+
+impl StandardCurve {
+    pub fn degree(&self) -> usize {
+        self.modulus[0]
+    }
+
+    pub fn order(&self) -> Result<UnsignedLongInt, ValidationError> {
+        UnsignedLongInt::from_str(self.order).map_err(|_| ValidationError::InvalidParameter)
+    }
+
+    /// Validation that doesn't need the field arithmetic: the modulus is irreducible and
+    /// n, h pass `check_order`
+    pub fn check_parameters(&self) -> Result<(), ValidationError> {
+        if !is_irreducible(self.modulus) {
+            return Err(ValidationError::ReducibleModulus);
+        }
+        check_order(self.degree(), &self.order()?, &UnsignedLongInt::from(self.cofactor))
+    }
+
+    /// Validated domain parameters over F, which must have the modulus of the curve
+    pub fn domain_parameters<F: PolynomialBasis>(&self) -> Result<DomainParameters<F>, ValidationError> {
+        if self.modulus != F::modulus() {
+            return Err(ValidationError::UnsupportedField);
+        }
+
+        let element = |s: &str| parse_element::<F>(s).ok_or(ValidationError::InvalidParameter);
+        let curve = Curve::new(element(self.a)?, element(self.b)?).map_err(|e| match e {
+            CurveError::Singular => ValidationError::Singular,
+            _ => ValidationError::InvalidParameter,
+        })?;
+        let base = Point::Affine { x: element(self.gx)?, y: element(self.gy)? };
+
+        let domain = DomainParameters::new(curve, base, self.order()?, UnsignedLongInt::from(self.cofactor));
+        validate_domain_parameters(&domain)?;
+        Ok(domain)
+    }
+
+    /// Full validation over the field type matching the modulus
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        const FIELDS: &[Validator] = &[
+            validate_over::<GF2Element<DIM>>,
+            validate_over::<Gf2m<Sect113>>,
+            validate_over::<Gf2m<Sect131>>,
+            validate_over::<Gf2m<Sect163>>,
+            validate_over::<Gf2m<Sect193>>,
+            validate_over::<Gf2m<Sect233>>,
+            validate_over::<Gf2m<Sect239>>,
+            validate_over::<Gf2m<Sect283>>,
+            validate_over::<Gf2m<Sect409>>,
+            validate_over::<Gf2m<Sect571>>,
+            validate_over::<Gf2m<Dstu167>>,
+            validate_over::<Gf2m<Dstu173>>,
+            validate_over::<Gf2m<Dstu179>>,
+            validate_over::<Gf2m<Dstu191>>,
+            validate_over::<Gf2m<Dstu233>>,
+            validate_over::<Gf2m<Dstu257>>,
+            validate_over::<Gf2m<Dstu307>>,
+            validate_over::<Gf2m<Dstu367>>,
+            validate_over::<Gf2m<Dstu431>>,
+        ];
+        FIELDS.iter()
+            .map(|validate| validate(self))
+            .find(|result| result != &Err(ValidationError::UnsupportedField))
+            .unwrap_or(Err(ValidationError::UnsupportedField))
+    }
+}
+
+type Validator = fn(&StandardCurve) -> Result<(), ValidationError>;
+
+fn validate_over<F: PolynomialBasis>(curve: &StandardCurve) -> Result<(), ValidationError> {
+    curve.domain_parameters::<F>().map(|_| ())
+}
+
+// Big-endian hex string to an element, None for other characters or a degree >= m
+fn parse_element<F: PolynomialBasis>(s: &str) -> Option<F> {
+    if s.is_empty() || !s.is_ascii() {
+        return None;
+    }
+    let words = s.as_bytes().rchunks(16)
+        .map(|chunk| u64::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok())
+        .collect::<Option<Vec<u64>>>()?;
+    F::from_words(&words)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use super::*;
+
+    #[test]
+    fn registry_test() -> Result<(), Box<dyn Error>> {
+        for curve in STANDARD_CURVES {
+            curve.check_parameters()?;
+            // G on the curve and n G = O over the field of the curve
+            curve.validate()?;
+            assert_eq!(curve.domain_parameters::<GF2Element<DIM>>(), Err(ValidationError::UnsupportedField));
+        }
+
+        let k163 = standard_curve("K-163").unwrap().domain_parameters::<Gf2m<Sect163>>()?;
+        assert_eq!(k163.curve.mul(&k163.base, &k163.order), Point::Infinity);
+        assert_eq!(standard_curve("B-233").unwrap().domain_parameters::<Gf2m<Sect163>>(), Err(ValidationError::UnsupportedField));
+
+        assert_eq!(standard_curve("sect233k1").map(|c| c.name), Some("K-233"));
+        assert_eq!(standard_curve("B-409").map(|c| c.degree()), Some(409));
+        assert_eq!(standard_curve("sect113r2").map(|c| c.degree()), Some(113));
+        assert_eq!(standard_curve("DSTU 4145 M431").map(|c| c.cofactor), Some(2));
+        assert_eq!(standard_curve("P-256"), None);
+        // 10 FIPS, 8 more SEC 2 and 10 DSTU 4145 curves, every name looks up its own entry
+        assert_eq!(STANDARD_CURVES.len(), 28);
+        assert!(STANDARD_CURVES.iter().all(|c| standard_curve(c.name) == Some(c)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn broken_parameters_test() {
+        let k163 = standard_curve("K-163").unwrap();
+
+        // 4 n is far outside the Hasse interval
+        let wrong_cofactor = StandardCurve { cofactor: 4, ..k163.clone() };
+        assert_eq!(wrong_cofactor.check_parameters(), Err(ValidationError::CofactorOutOfRange));
+        // an even number of terms gives the root 1
+        let reducible = StandardCurve { modulus: &[163, 7, 6, 0], ..k163.clone() };
+        assert_eq!(reducible.check_parameters(), Err(ValidationError::ReducibleModulus));
+        let not_hex = StandardCurve { order: "XYZ", ..k163.clone() };
+        assert_eq!(not_hex.check_parameters(), Err(ValidationError::InvalidParameter));
+        let too_long = StandardCurve { gx: "82FE13C0537BBC11ACAA07D793DE4E6D5E5C94EEE8", ..k163.clone() };
+        assert_eq!(too_long.validate(), Err(ValidationError::InvalidParameter));
+        // y + 1 puts G off the curve and n - 2 doesn't kill it
+        let off_curve = StandardCurve { gy: "289070FB05D38FF58321F2E800536D538CCDAA3D8", ..k163.clone() };
+        assert_eq!(off_curve.validate(), Err(ValidationError::BaseNotOnCurve));
+        let wrong_order = StandardCurve { order: "4000000000000000000020108A2E0CC0D99F8A5ED", ..k163.clone() };
+        assert_eq!(wrong_order.validate(), Err(ValidationError::WrongBaseOrder));
+    }
+}
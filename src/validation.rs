@@ -0,0 +1,272 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use vl_big_ints::UnsignedLongInt;
+use crate::domain::DomainParameters;
+use crate::field::PolynomialBasis;
+use crate::long_int::{is_probable_prime, mod_mul, rem};
+use crate::{MOD, MOD_DEG};
+
+/// Embedding degrees checked for the MOV reduction, the bound of SEC1 3.1.2.1
+pub const MOV_BOUND: usize = 100;
+
+/// Miller-Rabin rounds of the primality check on n
+pub const PRIMALITY_ROUNDS: usize = 40;
+
+const WORD: usize = u64::BITS as usize;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    ReducibleModulus,
+    UnsupportedField,
+    InvalidParameter,
+    Singular,
+    BaseNotOnCurve,
+    WrongBaseOrder,
+    CofactorOutOfRange,
+    Anomalous,
+    SmallEmbeddingDegree,
+    CompositeOrder,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ReducibleModulus => write!(f, "field modulus is reducible"),
+            Self::UnsupportedField => write!(f, "parameters are over a field other than the requested one"),
+            Self::InvalidParameter => write!(f, "parameter is not a valid hex number"),
+            Self::Singular => write!(f, "curve with b = 0 is singular"),
+            Self::BaseNotOnCurve => write!(f, "base point is the point at infinity or doesn't lie on the curve"),
+            Self::WrongBaseOrder => write!(f, "n G is not the point at infinity"),
+            Self::CofactorOutOfRange => write!(f, "h n is outside the Hasse interval or n <= 4 sqrt(q)"),
+            Self::Anomalous => write!(f, "curve is anomalous, #E = q"),
+            Self::SmallEmbeddingDegree => write!(f, "q^k = 1 mod n for a small k, the MOV reduction applies"),
+            Self::CompositeOrder => write!(f, "n is not prime"),
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+/// Checks of SEC1 3.1.2.2: the modulus is irreducible, b != 0, G is a point of order dividing
+/// the prime n, h n is a possible group order and the curve resists the anomalous and MOV attacks
+pub fn validate_domain_parameters<F: PolynomialBasis>(domain: &DomainParameters<F>) -> Result<(), ValidationError> {
+    if !is_irreducible(&F::modulus()) {
+        return Err(ValidationError::ReducibleModulus);
+    }
+    if domain.curve.b().is_zero() {
+        return Err(ValidationError::Singular);
+    }
+    if domain.base.is_infinity() || !domain.curve.is_on_curve(&domain.base) {
+        return Err(ValidationError::BaseNotOnCurve);
+    }
+    if !domain.curve.ld_mul(&domain.base, &domain.order).is_infinity() {
+        return Err(ValidationError::WrongBaseOrder);
+    }
+    check_order(F::DEGREE, &domain.order, &domain.cofactor)
+}
+
+/// Field independent part of the validation for a curve over GF(2^m) with #E = h n:
+/// Hasse bound |h n - q - 1| <= 2 sqrt(q), n > 4 sqrt(q), h n != q, q^k != 1 mod n for k <= MOV_BOUND
+/// and n passes PRIMALITY_ROUNDS rounds of Miller-Rabin
+pub fn check_order(m: usize, order: &UnsignedLongInt, cofactor: &UnsignedLongInt) -> Result<(), ValidationError> {
+    check_order_with_mov_bound(m, order, cofactor, MOV_BOUND)
+}
+
+/// `check_order` with another bound on the embedding degree, tiny fields can't reach MOV_BOUND
+pub fn check_order_with_mov_bound(m: usize, order: &UnsignedLongInt, cofactor: &UnsignedLongInt, mov_bound: usize) -> Result<(), ValidationError> {
+    let q = UnsignedLongInt::from(2).pow(&UnsignedLongInt::from(m as u64));
+    let group_order = cofactor.mul(order);
+
+    // both bounds squared to stay in integers
+    let q_plus_one = q.add(&UnsignedLongInt::from(1));
+    let distance = if group_order >= q_plus_one { group_order.sub(&q_plus_one) } else { q_plus_one.sub(&group_order) };
+    let four_q = q.mul(&UnsignedLongInt::from(4));
+    if distance.mul(&distance) > four_q || order.mul(order) <= four_q.mul(&UnsignedLongInt::from(4)) {
+        return Err(ValidationError::CofactorOutOfRange);
+    }
+
+    if group_order == q {
+        return Err(ValidationError::Anomalous);
+    }
+
+    let one = UnsignedLongInt::from(1);
+    let q_mod_n = rem(&q, order);
+    let mut power = q_mod_n.clone();
+    for _ in 0..mov_bound {
+        if power == one {
+            return Err(ValidationError::SmallEmbeddingDegree);
+        }
+        power = mod_mul(&power, &q_mod_n, order);
+    }
+
+    if !is_probable_prime(order, PRIMALITY_ROUNDS) {
+        return Err(ValidationError::CompositeOrder);
+    }
+
+    Ok(())
+}
+
+/// Exponents of the configured field modulus, the highest first
+pub fn modulus_exponents() -> Vec<usize> {
+    (0..=MOD_DEG).rev().filter(|&i| MOD[i / usize::BITS as usize] >> (i % usize::BITS as usize) & 1 == 1).collect()
+}
+
+/// Rabin's test for x^m + ... + 1 given by its exponents, the highest first:
+/// x^(2^m) = x mod f and gcd(x^(2^(m / p)) - x, f) = 1 for every prime p dividing m
+pub fn is_irreducible(exponents: &[usize]) -> bool {
+    let Some(&m) = exponents.first() else {
+        return false;
+    };
+    if m == 0 {
+        return false;
+    }
+
+    let mut f = vec![0u64; m / WORD + 1];
+    for &e in exponents {
+        f[e / WORD] ^= 1 << (e % WORD);
+    }
+    let x = poly_rem(&[2], &f);
+
+    let frobenius = |k: usize| (0..k).fold(x.clone(), |acc, _| poly_rem(&poly_sqr(&acc), &f));
+
+    if frobenius(m) != x {
+        return false;
+    }
+    prime_divisors(m).into_iter().all(|p| {
+        let mut h = frobenius(m / p);
+        poly_add_assign(&mut h, &x);
+        poly_deg(&poly_gcd(h, f.clone())) == Some(0)
+    })
+}
+
+fn prime_divisors(mut m: usize) -> Vec<usize> {
+    let mut primes = Vec::new();
+    let mut p = 2;
+    while p * p <= m {
+        if m.is_multiple_of(p) {
+            primes.push(p);
+            while m.is_multiple_of(p) {
+                m /= p;
+            }
+        }
+        p += 1;
+    }
+    if m > 1 {
+        primes.push(m);
+    }
+    primes
+}
+
+// Polynomials over GF(2) as little-endian 64-bit words, bit i is the coefficient at x^i
+
+fn poly_deg(a: &[u64]) -> Option<usize> {
+    a.iter().enumerate().rev()
+        .find(|(_, w)| **w != 0)
+        .map(|(i, w)| i * WORD + (WORD - 1 - w.leading_zeros() as usize))
+}
+
+fn poly_add_assign(a: &mut Vec<u64>, b: &[u64]) {
+    if a.len() < b.len() {
+        a.resize(b.len(), 0);
+    }
+    for (x, y) in a.iter_mut().zip(b) {
+        *x ^= y;
+    }
+}
+
+fn poly_sqr(a: &[u64]) -> Vec<u64> {
+    let mut out = vec![0u64; 2 * a.len()];
+    for i in (0..a.len() * WORD).filter(|&i| a[i / WORD] >> (i % WORD) & 1 == 1) {
+        out[2 * i / WORD] |= 1 << (2 * i % WORD);
+    }
+    out
+}
+
+// Remainder of the long division, trimmed to the length of f
+fn poly_rem(a: &[u64], f: &[u64]) -> Vec<u64> {
+    let df = poly_deg(f).expect("divisor must be non-zero");
+    let mut r = a.to_vec();
+    while let Some(dr) = poly_deg(&r).filter(|&dr| dr >= df) {
+        let shift = dr - df;
+        for i in (0..=df).filter(|&i| f[i / WORD] >> (i % WORD) & 1 == 1) {
+            r[(i + shift) / WORD] ^= 1 << ((i + shift) % WORD);
+        }
+    }
+    r.resize(f.len(), 0);
+    r
+}
+
+fn poly_gcd(mut a: Vec<u64>, mut b: Vec<u64>) -> Vec<u64> {
+    while poly_deg(&b).is_some() {
+        let r = poly_rem(&a, &b);
+        a = b;
+        b = r;
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::curve::{Curve, Point};
+    use crate::curve::tests::point_after;
+    use crate::domain::tests::test_domain;
+    use crate::long_int::div_rem;
+    use crate::GF2Element;
+    use super::*;
+
+    #[test]
+    fn irreducibility_test() {
+        assert!(is_irreducible(&modulus_exponents()));
+        assert!(is_irreducible(&[163, 7, 6, 3, 0]));
+        assert!(is_irreducible(&[4, 1, 0]));
+        assert!(is_irreducible(&[8, 4, 3, 1, 0]));
+        // x^4 + x^2 + 1 = (x^2 + x + 1)^2 has no roots but is reducible
+        assert!(!is_irreducible(&[4, 2, 0]));
+        // x^6 + x^5 + x^4 + x^3 + x^2 + x + 1 = (x^3 + x + 1)(x^3 + x^2 + 1) passes the first check of Rabin's test
+        assert!(!is_irreducible(&[6, 5, 4, 3, 2, 1, 0]));
+    }
+
+    #[test]
+    fn order_test() {
+        let n = UnsignedLongInt::from(1009);
+        // 1009 * 1 is within 2 * sqrt(1024) of 1025
+        assert_eq!(check_order(10, &n, &UnsignedLongInt::from(1)), Ok(()));
+        assert_eq!(check_order(10, &n, &UnsignedLongInt::from(2)), Err(ValidationError::CofactorOutOfRange));
+        assert_eq!(check_order(10, &UnsignedLongInt::from(1024), &UnsignedLongInt::from(1)), Err(ValidationError::Anomalous));
+        // 2^10 = 1 mod 1023, while 2^10 has order 252 modulo 1009
+        assert_eq!(check_order(10, &UnsignedLongInt::from(1023), &UnsignedLongInt::from(1)), Err(ValidationError::SmallEmbeddingDegree));
+        // 1003 = 17 * 59 and 2 has order 232 modulo 1003
+        assert_eq!(check_order(10, &UnsignedLongInt::from(1003), &UnsignedLongInt::from(1)), Err(ValidationError::CompositeOrder));
+    }
+
+    #[test]
+    fn domain_test() -> Result<(), Box<dyn Error>> {
+        let domain = test_domain()?;
+        validate_domain_parameters(&domain)?;
+
+        let wrong_order = DomainParameters { order: domain.order.add(&UnsignedLongInt::from(2)), ..domain.clone() };
+        assert_eq!(validate_domain_parameters(&wrong_order), Err(ValidationError::WrongBaseOrder));
+
+        let wrong_cofactor = DomainParameters { cofactor: UnsignedLongInt::from(2), ..domain.clone() };
+        assert_eq!(validate_domain_parameters(&wrong_cofactor), Err(ValidationError::CofactorOutOfRange));
+
+        let off_curve = DomainParameters { base: Point::Affine { x: GF2Element::ONE, y: GF2Element::ONE }, ..domain.clone() };
+        assert_eq!(validate_domain_parameters(&off_curve), Err(ValidationError::BaseNotOnCurve));
+
+        let infinity = DomainParameters { base: Point::Infinity, ..domain.clone() };
+        assert_eq!(validate_domain_parameters(&infinity), Err(ValidationError::BaseNotOnCurve));
+
+        let other_curve = DomainParameters { curve: Curve::new(GF2Element::ZERO, GF2Element::ONE)?, ..domain };
+        assert_eq!(validate_domain_parameters(&other_curve), Err(ValidationError::BaseNotOnCurve));
+
+        // the Koblitz curve a = b = 1 has #E = 2 n with n = 70393 * 759229 * c, yet 2Q is killed by n
+        let koblitz = Curve::new(GF2Element::ONE, GF2Element::ONE)?;
+        let (order, _) = div_rem(&koblitz.koblitz_group_order()?, &UnsignedLongInt::from(2));
+        let base = koblitz.double(&point_after(&koblitz, 2));
+        let composite = DomainParameters::new(koblitz, base, order, UnsignedLongInt::from(2));
+        assert_eq!(validate_domain_parameters(&composite), Err(ValidationError::CompositeOrder));
+
+        Ok(())
+    }
+}